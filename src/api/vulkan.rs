@@ -1,20 +1,26 @@
 use crate::api::vulkan_helper;
 use crate::core::layer_stack::LayerStack;
+use crate::renderer::camera::scaling::{Scaling, ScalingPolicy};
+use crate::renderer::frame_commands::FrameCommands;
 use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::readback::Readback;
 use crate::renderer::renderer::Renderer;
 use crate::ui::imgui_renderer::ImGuiRenderer;
+use image::RgbaImage;
 use imgui::DrawData;
 use log::error;
 use std::sync::Arc;
 use vulkano::{
     command_buffer::allocator::StandardCommandBufferAllocator,
-    device::{Device, Queue},
+    device::{Device, DeviceExtensions, Queue},
     format::Format,
     image::Image,
+    memory::allocator::StandardMemoryAllocator,
     pipeline::graphics::viewport::Viewport,
     render_pass::{Framebuffer, RenderPass},
     swapchain::{acquire_next_image, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo},
     sync,
+    sync::future::FenceSignalFuture,
     sync::GpuFuture,
     Validated,
     VulkanError
@@ -25,26 +31,62 @@ bitflags::bitflags! {
     pub struct RenderDirty: u32 {
         const NONE          = 0;
         const SWAPCHAIN     = 1 << 0;
-        const PIPELINE      = 1 << 1;
+        /// 视口是动态状态，只需更新渲染器记录的视口，不重建管线
+        const VIEWPORT      = 1 << 1;
         const COMMAND_BUF   = 1 << 2;
     }
 }
+/// Vulkan 创建参数
+#[derive(Clone, Debug)]
+pub struct VulkanConfig {
+    /// 深度附件格式，None 表示不使用深度缓冲
+    pub depth_format: Option<Format>,
+    /// 同时在途的帧数，建议 2~3
+    pub frames_in_flight: usize,
+    /// 窗口尺寸变化时的缩放策略，决定场景视口（黑边）和相机宽高比
+    pub scaling_policy: ScalingPolicy,
+}
+
+impl Default for VulkanConfig {
+    fn default() -> Self {
+        Self {
+            depth_format: Some(Format::D16_UNORM),
+            frames_in_flight: 2,
+            scaling_policy: ScalingPolicy::default(),
+        }
+    }
+}
+
+type FrameFence = FenceSignalFuture<Box<dyn GpuFuture>>;
+
 pub struct Vulkan {
     pub device: Arc<Device>,
     pub queue: Arc<Queue>,
-    pub surface: Arc<Surface>,
-    pub swapchain: Arc<Swapchain>,
-    pub swapchain_images: Vec<Arc<Image>>,
+    /// 无窗口模式下为 None
+    pub surface: Option<Arc<Surface>>,
+    /// 无窗口模式下为 None
+    pub swapchain: Option<Arc<Swapchain>>,
+    /// 交换链图像，无窗口模式下为单张离屏图像
+    pub images: Vec<Arc<Image>>,
     pub render_pass: Arc<RenderPass>,
     pub frame_buffers: Vec<Arc<Framebuffer>>,
+    /// 场景视口，按缩放策略留出黑边；ImGui 始终使用整个窗口
     pub viewport: Viewport,
+    /// 修改后需标记 `RenderDirty::VIEWPORT` 才会生效
+    pub scaling_policy: ScalingPolicy,
     pub command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+    pub memory_allocator: Arc<StandardMemoryAllocator>,
+
+    /// 每个在途帧的栅栏，帧槽被复用前需等待
+    frame_fences: Vec<Option<Arc<FrameFence>>>,
+    /// 当前帧槽
+    frame_index: usize,
 
     pub dirty: RenderDirty,
 }
 
 impl Vulkan {
-    pub fn new(window: Arc<Window>) -> Vulkan {
+    pub fn new(window: Arc<Window>, config: VulkanConfig) -> Vulkan {
 
         let library = vulkan_helper::get_library();
 
@@ -58,7 +100,12 @@ impl Vulkan {
             Arc::clone(&window)
         );
 
-        let (device, queue) = vulkan_helper::get_device_and_queue(Arc::clone(&instance));
+        let device_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::default()
+        };
+
+        let (device, queue) = vulkan_helper::get_device_and_queue(Arc::clone(&instance), device_extensions);
 
         let (swapchain, images) = vulkan_helper::get_swapchain_and_images(
             Arc::clone(&device),
@@ -67,33 +114,145 @@ impl Vulkan {
         );
 
         let allocator = Arc::new(vulkan_helper::get_command_buffer_allocator(Arc::clone(&device)));
+        let memory_allocator = vulkan_helper::get_mem_allocator(Arc::clone(&device));
 
-        let viewport = Viewport {
-            extent: window.clone().inner_size().into(),
-            ..Viewport::default()
-        };
+        let viewport = Self::scene_viewport(
+            &config.scaling_policy.apply(window.inner_size().into(), window.scale_factor() as f32)
+        );
+
+        let render_pass = vulkan_helper::get_render_pass(
+            device.clone(),
+            Format::R8G8B8A8_UNORM,
+            config.depth_format
+        );
+
+        let framebuffers: Vec<Arc<Framebuffer>> = vulkan_helper::get_framebuffers(
+            images.clone(),
+            Arc::clone(&render_pass),
+            memory_allocator.clone(),
+        );
+
+        Vulkan {
+            device,
+            queue,
+            surface: Some(surface),
+            swapchain: Some(swapchain),
+            images,
+            render_pass,
+            frame_buffers: framebuffers,
+            viewport,
+            scaling_policy: config.scaling_policy,
+            command_buffer_allocator: allocator,
+            memory_allocator,
+            frame_fences: vec![None; config.frames_in_flight.max(1)],
+            frame_index: 0,
+            dirty: RenderDirty::NONE,
+        }
+    }
+
+    /// 创建无窗口的 Vulkan，渲染到一张离屏颜色图像上
+    ///
+    /// 不需要 Surface 和交换链，可在 CI 或服务器上配合 lavapipe 等软件驱动使用
+    pub fn new_headless(extent: [u32; 2], config: VulkanConfig) -> Vulkan {
+        let library = vulkan_helper::get_library();
+
+        let instance = vulkan_helper::get_headless_instance(Arc::clone(&library));
 
-        let render_pass = vulkan_helper::get_render_pass(device.clone(), Format::R8G8B8A8_UNORM);
+        let (device, queue) = vulkan_helper::get_device_and_queue(
+            Arc::clone(&instance),
+            DeviceExtensions::default()
+        );
+
+        let allocator = Arc::new(vulkan_helper::get_command_buffer_allocator(Arc::clone(&device)));
+        let memory_allocator = vulkan_helper::get_mem_allocator(Arc::clone(&device));
+
+        let viewport = Self::scene_viewport(&config.scaling_policy.apply(extent, 1.0));
+
+        let images = vulkan_helper::get_offscreen_images(
+            memory_allocator.clone(),
+            extent,
+            Format::R8G8B8A8_UNORM
+        );
+
+        let render_pass = vulkan_helper::get_render_pass(
+            device.clone(),
+            Format::R8G8B8A8_UNORM,
+            config.depth_format
+        );
 
         let framebuffers: Vec<Arc<Framebuffer>> = vulkan_helper::get_framebuffers(
             images.clone(),
             Arc::clone(&render_pass),
+            memory_allocator.clone(),
         );
 
         Vulkan {
             device,
             queue,
-            surface,
-            swapchain,
-            swapchain_images: images,
+            surface: None,
+            swapchain: None,
+            images,
             render_pass,
             frame_buffers: framebuffers,
             viewport,
+            scaling_policy: config.scaling_policy,
             command_buffer_allocator: allocator,
+            memory_allocator,
+            frame_fences: vec![None; config.frames_in_flight.max(1)],
+            frame_index: 0,
             dirty: RenderDirty::NONE,
         }
     }
 
+    pub fn is_headless(&self) -> bool {
+        self.swapchain.is_none()
+    }
+
+    pub fn extent(&self) -> [u32; 2] {
+        let [width, height, _] = self.images[0].extent();
+        [width, height]
+    }
+
+    /// 按当前缩放策略计算某个窗口尺寸下的视口和相机参数
+    pub fn scaling(&self, window_size: [u32; 2], scale_factor: f32) -> Scaling {
+        self.scaling_policy.apply(window_size, scale_factor)
+    }
+
+    fn scene_viewport(scaling: &Scaling) -> Viewport {
+        Viewport {
+            offset: scaling.viewport.offset.into(),
+            extent: scaling.viewport.size.into(),
+            depth_range: 0.0..=1.0
+        }
+    }
+
+    /// 覆盖整个帧缓冲的视口
+    fn full_viewport(&self) -> Viewport {
+        let [width, height] = self.extent();
+
+        Viewport {
+            offset: [0.0, 0.0],
+            extent: [width as f32, height as f32],
+            depth_range: 0.0..=1.0
+        }
+    }
+
+    /// 同时在途的帧数
+    pub fn frames_in_flight(&self) -> usize {
+        self.frame_fences.len()
+    }
+
+    /// 等待所有在途帧执行完毕，关闭或销毁资源前调用
+    pub fn wait_for_frames(&mut self) {
+        for fence in self.frame_fences.iter_mut() {
+            if let Some(fence) = fence.take() {
+                if let Err(e) = fence.wait(None) {
+                    error!("等待帧栅栏失败: {}", e);
+                }
+            }
+        }
+    }
+
     pub fn submit(
         &mut self,
         renderer: &mut Renderer,
@@ -103,9 +262,27 @@ impl Vulkan {
         imgui_renderer: &mut ImGuiRenderer,
         draw_data: &DrawData
     ) {
+        let Some(swapchain) = self.swapchain.clone() else {
+            self.render_offscreen(renderer, layer_stack, clear_color, map);
+            return;
+        };
+
+        // 释放已执行完毕的帧持有的资源
+        for fence in self.frame_fences.iter_mut() {
+            if fence.as_ref().is_some_and(|fence| fence.is_signaled().unwrap_or(false)) {
+                *fence = None;
+            }
+        }
+
+        // 复用帧槽前等待它上一次提交的栅栏，其余帧继续在 GPU 上执行
+        if let Some(fence) = self.frame_fences[self.frame_index].take() {
+            if let Err(e) = fence.wait(None) {
+                error!("等待帧栅栏失败: {}", e);
+            }
+        }
 
         let (image_i, suboptimal, acquire_future) =
-            match acquire_next_image(self.swapchain.clone(), None)
+            match acquire_next_image(swapchain.clone(), None)
                 .map_err(Validated::unwrap)
             {
                 Ok(result) => result,
@@ -126,24 +303,37 @@ impl Vulkan {
             clear_color,
             layer_stack,
             map,
-            imgui_renderer,
-            draw_data,
-            self.viewport.clone()
+            Some((imgui_renderer, draw_data)),
+            self.full_viewport()
         );
 
-        let execution = sync::now(self.device.clone())
+        // 上一帧的栅栏，保证同一队列上的提交顺序
+        let previous_index = (self.frame_index + self.frame_fences.len() - 1) % self.frame_fences.len();
+        let previous_future = match self.frame_fences[previous_index].clone() {
+            Some(fence) => fence.boxed(),
+            None => sync::now(self.device.clone()).boxed(),
+        };
+
+        let execution = previous_future
             .join(acquire_future)
             .then_execute(self.queue.clone(), command_buffer)
             .unwrap()
             .then_swapchain_present(
                 self.queue.clone(),
-                SwapchainPresentInfo::swapchain_image_index(self.swapchain.clone(), image_i),
+                SwapchainPresentInfo::swapchain_image_index(swapchain, image_i),
             )
+            .boxed()
             .then_signal_fence_and_flush();
 
         match execution.map_err(Validated::unwrap) {
             Ok(future) => {
-                future.wait(None).unwrap();
+                // 截图需要立即读回，只在有截图时阻塞
+                if renderer.has_pending_screenshot() {
+                    future.wait(None).unwrap();
+                    renderer.finish_screenshot();
+                }
+
+                self.frame_fences[self.frame_index] = Some(Arc::new(future));
             }
             Err(VulkanError::OutOfDate) => {
                 self.dirty.insert(RenderDirty::SWAPCHAIN);
@@ -152,6 +342,60 @@ impl Vulkan {
                 error!("failed to flush future: {e}")
             }
         }
+
+        self.frame_index = (self.frame_index + 1) % self.frame_fences.len();
+    }
+
+    /// 无窗口模式下渲染一帧：完整执行层栈的 on_render，不获取/呈现交换链图像
+    pub fn render_offscreen(
+        &mut self,
+        renderer: &mut Renderer,
+        layer_stack: &mut LayerStack,
+        clear_color: [f32; 4],
+        map: &mut ImageBufferManager,
+    ) {
+        let command_buffer = renderer.render_frame(
+            self.frame_buffers[0].clone(),
+            clear_color,
+            layer_stack,
+            map,
+            None,
+            self.full_viewport()
+        );
+
+        let execution = sync::now(self.device.clone())
+            .then_execute(self.queue.clone(), command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush();
+
+        match execution.map_err(Validated::unwrap) {
+            Ok(future) => {
+                future.wait(None).unwrap();
+                renderer.finish_screenshot();
+            }
+            Err(e) => {
+                error!("failed to flush future: {e}")
+            }
+        }
+    }
+
+    /// 将离屏图像读回 CPU，仅无窗口模式可用
+    pub fn read_pixels(&self) -> RgbaImage {
+        assert!(self.is_headless(), "read_pixels 仅支持无窗口模式");
+
+        let mut frame = FrameCommands::new(self.command_buffer_allocator.clone(), self.queue.clone());
+
+        let readback = Readback::record(&mut frame, self.images[0].clone(), self.memory_allocator.clone());
+
+        sync::now(self.device.clone())
+            .then_execute(self.queue.clone(), frame.builder.build().unwrap())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap_or_else(|e| panic!("回读图像失败: {}", e))
+            .wait(None)
+            .unwrap();
+
+        readback.to_image()
     }
 
     pub fn recreate_swapchain(&mut self, window: Arc<Window>, renderer: &mut Renderer) {
@@ -159,34 +403,41 @@ impl Vulkan {
             return;
         }
 
+        let Some(swapchain) = self.swapchain.clone() else {
+            return;
+        };
+
         if self.dirty.contains(RenderDirty::SWAPCHAIN) {
             let new_dimensions = window.clone().inner_size();
 
-            let (new_swapchain, new_images) = self.swapchain
+            let (new_swapchain, new_images) = swapchain
                 .recreate(SwapchainCreateInfo{
                     image_extent: new_dimensions.into(),
-                    ..self.swapchain.create_info()
+                    ..swapchain.create_info()
                 })
                 .expect("重建交换链失败！");
 
-            self.swapchain = new_swapchain;
-            self.swapchain_images = new_images.clone();
-            self.frame_buffers = vulkan_helper::get_framebuffers(new_images, self.render_pass.clone());
+            self.swapchain = Some(new_swapchain);
+            self.images = new_images.clone();
+            self.frame_buffers = vulkan_helper::get_framebuffers(
+                new_images,
+                self.render_pass.clone(),
+                self.memory_allocator.clone()
+            );
 
             self.dirty.remove(RenderDirty::SWAPCHAIN);
         }
 
-        if self.dirty.contains(RenderDirty::PIPELINE) {
-            let viewport = Viewport {
-                offset: [0.0,0.0],
-                extent: window.inner_size().into(),
-                depth_range: 0.0..=1.0
-            };
+        if self.dirty.contains(RenderDirty::VIEWPORT) {
+            // 黑边区域只清屏不绘制
+            let viewport = Self::scene_viewport(
+                &self.scaling(window.inner_size().into(), window.scale_factor() as f32)
+            );
 
             self.viewport = viewport.clone();
 
-            renderer.recreate_pipeline(viewport);
-            self.dirty.remove(RenderDirty::PIPELINE);
+            renderer.set_viewport(viewport);
+            self.dirty.remove(RenderDirty::VIEWPORT);
         }
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
+use crate::renderer::material::PipelineState;
 use crate::renderer::shaders::Shader;
-use crate::renderer::shapes::mesh::AzerVertex;
+use crate::renderer::shapes::mesh::{AzerVertex, InstanceData, Vertex3D};
 use log::error;
 use std::collections::BTreeMap;
 use std::sync::Arc;
@@ -15,15 +16,16 @@ use vulkano::image::view::ImageView;
 use vulkano::image::{Image, ImageCreateInfo, ImageType, ImageUsage};
 use vulkano::instance::{Instance, InstanceCreateInfo, InstanceExtensions};
 use vulkano::memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator};
-use vulkano::pipeline::graphics::color_blend::{ColorBlendAttachmentState, ColorBlendState};
+use vulkano::pipeline::graphics::color_blend::ColorBlendState;
+use vulkano::pipeline::graphics::depth_stencil::{CompareOp, DepthState, DepthStencilState};
 use vulkano::pipeline::graphics::input_assembly::InputAssemblyState;
 use vulkano::pipeline::graphics::multisample::MultisampleState;
 use vulkano::pipeline::graphics::rasterization::RasterizationState;
 use vulkano::pipeline::graphics::vertex_input::{Vertex, VertexDefinition};
-use vulkano::pipeline::graphics::viewport::{Viewport, ViewportState};
+use vulkano::pipeline::graphics::viewport::{Scissor, Viewport, ViewportState};
 use vulkano::pipeline::graphics::GraphicsPipelineCreateInfo;
 use vulkano::pipeline::layout::PipelineDescriptorSetLayoutCreateInfo;
-use vulkano::pipeline::{GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo};
+use vulkano::pipeline::{DynamicState, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo};
 use vulkano::render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass};
 use vulkano::swapchain::{PresentMode, Surface, Swapchain, SwapchainCreateInfo};
 use vulkano::{single_pass_renderpass, VulkanLibrary};
@@ -56,6 +58,30 @@ pub fn get_instance(
     ).unwrap_or_else(|err| panic!("无法创建Vulkan实例: {}", err))
 }
 
+/// 获取无窗口模式下的 VulkanInstance（不启用任何 Surface 扩展）
+pub fn get_headless_instance(lib: Arc<VulkanLibrary>) -> Arc<Instance> {
+    Instance::new(
+        lib,
+        InstanceCreateInfo::default()
+    ).unwrap_or_else(|err| panic!("无法创建Vulkan实例: {}", err))
+}
+
+/// 是否有支持图形队列的物理设备，没有 Vulkan 驱动的环境中无窗口测试据此跳过
+pub fn has_graphics_device() -> bool {
+    let Ok(library) = VulkanLibrary::new() else {
+        return false;
+    };
+
+    let Ok(instance) = Instance::new(library, InstanceCreateInfo::default()) else {
+        return false;
+    };
+
+    instance.enumerate_physical_devices()
+        .is_ok_and(|mut devices| devices.any(|device| {
+            get_required_queue_family_index(device.as_ref(), QueueFlags::GRAPHICS).is_some()
+        }))
+}
+
 /// 获取 VulkanSurface
 pub fn get_surface(
     ins: Arc<Instance>,
@@ -68,6 +94,7 @@ pub fn get_surface(
 /// 获取 Device 和 Queue
 pub fn get_device_and_queue(
     ins: Arc<Instance>,
+    device_extensions: DeviceExtensions,
 ) -> (Arc<Device>, Arc<Queue>) {
     // 遍历物理设备搜寻符合要求的设备
     let physical_devices = ins.enumerate_physical_devices()
@@ -75,6 +102,11 @@ pub fn get_device_and_queue(
 
     for physical_device in physical_devices {
 
+        // 检测是否支持所需扩展
+        if !physical_device.supported_extensions().contains(&device_extensions) {
+            continue;
+        }
+
         // 检测是否支持图形队列
         if let Some(idx) = get_required_queue_family_index(
             physical_device.as_ref(), QueueFlags::GRAPHICS)
@@ -92,11 +124,6 @@ pub fn get_device_and_queue(
                 ..QueueCreateInfo::default()
             };
 
-            let device_extensions = DeviceExtensions {
-                khr_swapchain: true,
-                ..DeviceExtensions::default()
-            };
-
             let device_create_info = DeviceCreateInfo {
                 queue_create_infos: vec![queue_create_info],
                 enabled_extensions: device_extensions,
@@ -124,7 +151,8 @@ pub fn get_swapchain_and_images(
     let swapchain_create_info = SwapchainCreateInfo {
         image_format: Format::R8G8B8A8_UNORM,
         image_extent: win.inner_size().into(),
-        image_usage: ImageUsage::COLOR_ATTACHMENT,
+        // TRANSFER_SRC 用于截图回读
+        image_usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
         present_mode: PresentMode::Fifo,
         ..SwapchainCreateInfo::default()
     };
@@ -136,6 +164,52 @@ pub fn get_swapchain_and_images(
     ).unwrap_or_else(|err| panic!("图像交换链创建失败: {}", err))
 }
 
+/// 获取离屏渲染使用的颜色图像
+pub fn get_offscreen_images(
+    allocator: Arc<StandardMemoryAllocator>,
+    extent: [u32; 2],
+    format: Format,
+) -> Vec<Arc<Image>> {
+    let image = Image::new(
+        allocator,
+        ImageCreateInfo {
+            image_type: ImageType::Dim2d,
+            format,
+            extent: [extent[0], extent[1], 1],
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+            ..ImageCreateInfo::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+            ..AllocationCreateInfo::default()
+        },
+    ).unwrap_or_else(|err| panic!("离屏图像创建失败: {}", err));
+
+    vec![image]
+}
+
+/// 获取渲染目标使用的颜色图像，既可作为颜色附件也可被着色器采样
+pub fn get_render_target_image(
+    allocator: Arc<StandardMemoryAllocator>,
+    extent: [u32; 2],
+    format: Format,
+) -> Arc<Image> {
+    Image::new(
+        allocator,
+        ImageCreateInfo {
+            image_type: ImageType::Dim2d,
+            format,
+            extent: [extent[0].max(1), extent[1].max(1), 1],
+            usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED | ImageUsage::TRANSFER_SRC,
+            ..ImageCreateInfo::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+            ..AllocationCreateInfo::default()
+        },
+    ).unwrap_or_else(|err| panic!("渲染目标图像创建失败: {}", err))
+}
+
 /// 获取 CommandBufferAllocator
 pub fn get_command_buffer_allocator(
     device: Arc<Device>,
@@ -147,42 +221,101 @@ pub fn get_command_buffer_allocator(
 }
 
 /// 获取 RenderPass
+///
+/// `depth_format` 为 Some 时附带深度附件
 pub fn get_render_pass(
     device: Arc<Device>,
     format: Format,
+    depth_format: Option<Format>,
 ) -> Arc<RenderPass> {
-    let render_pass = single_pass_renderpass!(
-        device,
-        attachments: {
-            foo: {
-                format: format,
-                samples: 1,
-                load_op: Clear,
-                store_op: Store,
+    let render_pass = match depth_format {
+        Some(depth_format) => single_pass_renderpass!(
+            device,
+            attachments: {
+                foo: {
+                    format: format,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+                depth: {
+                    format: depth_format,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: DontCare,
+                },
             },
-        },
-        pass: {
-            color: [foo],
-            depth_stencil: {},
-        }
-    )
+            pass: {
+                color: [foo],
+                depth_stencil: {depth},
+            }
+        ),
+        None => single_pass_renderpass!(
+            device,
+            attachments: {
+                foo: {
+                    format: format,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+            },
+            pass: {
+                color: [foo],
+                depth_stencil: {},
+            }
+        ),
+    }
         .unwrap_or_else(|err| panic!("创建渲染令牌: {}", err));
     render_pass
 }
 
+/// 获取深度图像
+pub fn get_depth_image(
+    allocator: Arc<StandardMemoryAllocator>,
+    extent: [u32; 2],
+    format: Format,
+) -> Arc<Image> {
+    Image::new(
+        allocator,
+        ImageCreateInfo {
+            image_type: ImageType::Dim2d,
+            format,
+            extent: [extent[0], extent[1], 1],
+            usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT | ImageUsage::TRANSIENT_ATTACHMENT,
+            ..ImageCreateInfo::default()
+        },
+        AllocationCreateInfo::default(),
+    ).unwrap_or_else(|err| panic!("深度图像创建失败: {}", err))
+}
+
 /// 获取 Framebuffers
+///
+/// 渲染通道带有深度附件时，为每个帧缓冲创建对应尺寸的深度图像
 pub fn get_framebuffers(
     images: Vec<Arc<Image>>,
     render_pass: Arc<RenderPass>,
+    allocator: Arc<StandardMemoryAllocator>,
 ) -> Vec<Arc<Framebuffer>> {
     let mut framebuffers: Vec<Arc<Framebuffer>> = Vec::new();
 
+    let depth_format = render_pass.attachments().get(1).map(|attachment| attachment.format);
+
     images.iter().for_each(|image| {
         let view = ImageView::new_default(image.clone()).unwrap();
+
+        let mut attachments = vec![view];
+
+        if let Some(depth_format) = depth_format {
+            let [width, height, _] = image.extent();
+            let depth = get_depth_image(allocator.clone(), [width, height], depth_format);
+            attachments.push(ImageView::new_default(depth).unwrap());
+        }
+
         let framebuffer = Framebuffer::new(
             Arc::clone(&render_pass),
             FramebufferCreateInfo {
-                attachments: vec![view],
+                attachments,
                 ..FramebufferCreateInfo::default()
             }
         ).unwrap_or_else(|err| panic!("创建帧缓冲区失败: {}", err));
@@ -259,22 +392,76 @@ where T: BufferContents
     ).unwrap()
 }
 
+/// 获取包含多个 uniform 缓冲的描述符集，用于材质的自定义 uniform 块
+pub fn get_uniform_descriptor_set(
+    uniforms: &[(u32, Subbuffer<[u8]>)],
+    layout: Arc<DescriptorSetLayout>,
+    allocator: Arc<StandardDescriptorSetAllocator>
+) -> Result<Arc<DescriptorSet>, String> {
+    let writes = uniforms.iter()
+        .map(|(binding, buffer)| vulkano::descriptor_set::WriteDescriptorSet::buffer(*binding, buffer.clone()));
+
+    DescriptorSet::new(
+        allocator,
+        layout,
+        writes,
+        []
+    ).map_err(|e| format!("创建材质描述符集失败: {}", e))
+}
+
 /// 获取 GraphicsPipeline
 pub fn get_graphics_pipeline(
     device: Arc<Device>,
     render_pass: Arc<RenderPass>,
     shader: Arc<dyn Shader>,
-    viewport: Viewport
+    state: PipelineState,
 ) -> Arc<GraphicsPipeline> {
-    let vs = shader.vs().entry_point("main").unwrap();
-    let fs = shader.fs().entry_point("main").unwrap();
+    try_get_graphics_pipeline(device, render_pass, shader, state)
+        .unwrap_or_else(|e| {
+            error!("创建图形管线失败: {}", e);
+            panic!("创建图形管线失败")
+        })
+}
 
-    let vertex_input_state = AzerVertex::per_vertex()
-        .definition(&vs)
+/// 同 `get_graphics_pipeline`，失败时返回错误信息而不是 panic，用于运行时加载的着色器
+pub fn try_get_graphics_pipeline(
+    device: Arc<Device>,
+    render_pass: Arc<RenderPass>,
+    shader: Arc<dyn Shader>,
+    state: PipelineState,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    try_get_graphics_pipeline_for::<AzerVertex>(device, render_pass, shader, state)
+}
+
+/// 获取 3D 网格使用的 GraphicsPipeline
+pub fn get_graphics_pipeline_3d(
+    device: Arc<Device>,
+    render_pass: Arc<RenderPass>,
+    shader: Arc<dyn Shader>,
+    state: PipelineState,
+) -> Arc<GraphicsPipeline> {
+    try_get_graphics_pipeline_for::<Vertex3D>(device, render_pass, shader, state)
         .unwrap_or_else(|e| {
-            error!("获取顶点输入状态失败: {}", e);
-            panic!("获取顶点输入状态失败")
-        });
+            error!("创建3D图形管线失败: {}", e);
+            panic!("创建3D图形管线失败")
+        })
+}
+
+/// 以 `V` 为每顶点输入、`InstanceData` 为每实例输入创建管线
+///
+/// 视口和裁剪矩形是动态状态，需在绘制前通过命令缓冲设置，窗口尺寸变化时无需重建管线
+pub fn try_get_graphics_pipeline_for<V: Vertex>(
+    device: Arc<Device>,
+    render_pass: Arc<RenderPass>,
+    shader: Arc<dyn Shader>,
+    state: PipelineState,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let vs = shader.vs().entry_point("main").ok_or("顶点着色器缺少 main 入口")?;
+    let fs = shader.fs().entry_point("main").ok_or("片段着色器缺少 main 入口")?;
+
+    let vertex_input_state = [V::per_vertex(), InstanceData::per_instance()]
+        .definition(&vs)
+        .map_err(|e| format!("获取顶点输入状态失败: {}", e))?;
 
     let stages = [
         PipelineShaderStageCreateInfo::new(vs),
@@ -285,34 +472,63 @@ pub fn get_graphics_pipeline(
     let layout = PipelineLayout::new(
         device.clone(),
         PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
-            .into_pipeline_layout_create_info(device.clone()).unwrap()
-    ).unwrap();
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|e| format!("创建管线布局失败: {:?}", e))?
+    ).map_err(|e| format!("创建管线布局失败: {}", e))?;
 
     let subpass = Subpass::from(render_pass.clone(), 0).unwrap();
 
-    let pipeline = GraphicsPipeline::new(
+    GraphicsPipeline::new(
         device.clone(),
         None,
         GraphicsPipelineCreateInfo {
             stages: stages.into_iter().collect(),
             vertex_input_state: Some(vertex_input_state),
             input_assembly_state: Some(InputAssemblyState::default()),
-            viewport_state: Some(ViewportState {
-                viewports: [viewport].into_iter().collect(),
-                ..ViewportState::default()
+            viewport_state: Some(ViewportState::default()),
+            rasterization_state: Some(RasterizationState {
+                cull_mode: state.cull_mode,
+                ..RasterizationState::default()
             }),
-            rasterization_state: Some(RasterizationState::default()),
             multisample_state: Some(MultisampleState::default()),
             color_blend_state: Some(ColorBlendState::with_attachment_states(
                 subpass.num_color_attachments(),
-                ColorBlendAttachmentState::default()
+                state.blend_mode.attachment_state()
             )),
+            depth_stencil_state: subpass.has_depth().then(|| DepthStencilState {
+                depth: state.depth_test.then_some(DepthState {
+                    write_enable: state.depth_write,
+                    compare_op: CompareOp::LessOrEqual,
+                }),
+                ..DepthStencilState::default()
+            }),
             subpass: Some(subpass.into()),
+            dynamic_state: [DynamicState::Viewport, DynamicState::Scissor].into_iter().collect(),
             ..GraphicsPipelineCreateInfo::layout(layout)
         }
-    ).unwrap();
+    ).map_err(|e| format!("创建图形管线失败: {}", e))
+}
 
-    pipeline
+/// 覆盖整个视口的裁剪矩形
+pub fn get_viewport_scissor(viewport: &Viewport) -> Scissor {
+    Scissor {
+        offset: [viewport.offset[0].max(0.0) as u32, viewport.offset[1].max(0.0) as u32],
+        extent: [viewport.extent[0].max(0.0) as u32, viewport.extent[1].max(0.0) as u32],
+    }
+}
+
+/// 两个裁剪矩形的交集，不相交时返回空矩形
+pub fn get_scissor_intersection(a: Scissor, b: Scissor) -> Scissor {
+    let min = [a.offset[0].max(b.offset[0]), a.offset[1].max(b.offset[1])];
+    let max = [
+        a.offset[0].saturating_add(a.extent[0]).min(b.offset[0].saturating_add(b.extent[0])),
+        a.offset[1].saturating_add(a.extent[1]).min(b.offset[1].saturating_add(b.extent[1])),
+    ];
+
+    Scissor {
+        offset: min,
+        extent: [max[0].saturating_sub(min[0]), max[1].saturating_sub(min[1])],
+    }
 }
 
 pub fn get_default_texture_image_2d(allocator: Arc<StandardMemoryAllocator>) -> Arc<Image> {
@@ -378,6 +594,25 @@ pub fn get_staging(pixels: Vec<u8>,allocator: Arc<StandardMemoryAllocator>) -> S
     })
 }
 
+/// 获取回读缓冲区，用于将 GPU 图像拷贝回 CPU
+pub fn get_readback_buffer(size: u64, allocator: Arc<StandardMemoryAllocator>) -> Subbuffer<[u8]> {
+    Buffer::new_slice::<u8>(
+        allocator,
+        BufferCreateInfo {
+            usage: BufferUsage::TRANSFER_DST,
+            ..BufferCreateInfo::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+            ..AllocationCreateInfo::default()
+        },
+        size,
+    ).unwrap_or_else(|e| {
+        error!("创建回读缓冲区失败: {}", e);
+        panic!("无法创建回读缓冲区: {:?}", e);
+    })
+}
+
 /// 获取 CommandBuffer Allocator
 pub fn get_cmd_buffer_allocator(device: Arc<Device>) -> Arc<StandardCommandBufferAllocator> {
     Arc::new(StandardCommandBufferAllocator::new(
@@ -409,4 +644,57 @@ fn get_required_queue_family_index(physical_device: &PhysicalDevice, required_fl
         }
     }
     None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scissor(offset: [u32; 2], extent: [u32; 2]) -> Scissor {
+        Scissor { offset, extent }
+    }
+
+    #[test]
+    fn scissor_intersection_of_overlapping_rects() {
+        let a = scissor([0, 0], [100, 80]);
+        let b = scissor([60, 40], [100, 100]);
+
+        assert_eq!(get_scissor_intersection(a, b), scissor([60, 40], [40, 40]));
+        assert_eq!(get_scissor_intersection(b, a), scissor([60, 40], [40, 40]));
+    }
+
+    #[test]
+    fn scissor_intersection_of_contained_rect() {
+        let outer = scissor([10, 10], [200, 200]);
+        let inner = scissor([50, 60], [20, 30]);
+
+        assert_eq!(get_scissor_intersection(outer, inner), inner);
+        assert_eq!(get_scissor_intersection(inner, outer), inner);
+    }
+
+    #[test]
+    fn scissor_intersection_of_disjoint_rects_is_empty() {
+        let left = scissor([0, 0], [100, 100]);
+        let right = scissor([100, 0], [100, 100]);
+        let far = scissor([300, 300], [10, 10]);
+
+        // 只相邻不重叠
+        assert_eq!(get_scissor_intersection(left, right).extent[0], 0);
+        assert_eq!(get_scissor_intersection(left, far).extent, [0, 0]);
+        assert_eq!(get_scissor_intersection(far, left).extent, [0, 0]);
+    }
+
+    #[test]
+    fn viewport_scissor_covers_viewport() {
+        let viewport = Viewport {
+            offset: [640.0, 0.0],
+            extent: [640.0, 720.0],
+            depth_range: 0.0..=1.0,
+        };
+        assert_eq!(get_viewport_scissor(&viewport), scissor([640, 0], [640, 720]));
+
+        // 分屏右侧视图中越过左边界的裁剪矩形被限制在视图内
+        let clip = scissor([600, 100], [100, 100]);
+        assert_eq!(get_scissor_intersection(get_viewport_scissor(&viewport), clip), scissor([640, 100], [60, 100]));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,401 @@
+use crate::asset::assets::Assets;
+use crate::asset::handle::{AssetId, Handle, LoadState};
+use crate::asset::hot_reload::HotReload;
+use crate::asset::texture::Texture;
+use crate::asset::vfs;
+use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::renderer2d::texture_atlas::{TextureAtlas, ATLAS_PAGE_SIZE};
+use image::RgbaImage;
+use log::{error, info, warn};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
+use vulkano::descriptor_set::layout::DescriptorSetLayout;
+use vulkano::descriptor_set::DescriptorSet;
+use vulkano::device::Device;
+use vulkano::image::sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo};
+use vulkano::memory::allocator::StandardMemoryAllocator;
+
+/// 可打包进图集的图片扩展名
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "bmp", "tga"];
+
+/// 后台解码结果
+struct Decoded {
+    id: AssetId,
+    path: String,
+    result: Result<RgbaImage, String>,
+    /// 热重载时为 true，替换已加载的纹理
+    reload: bool,
+}
+
+/// 资源服务器：异步加载资源，通过带类型的句柄访问，按引用计数回收
+pub struct AssetServer {
+    textures: Assets<Texture>,
+    atlas: TextureAtlas,
+    next_id: u64,
+
+    sender: Sender<Decoded>,
+    receiver: Receiver<Decoded>,
+
+    /// 开发模式下监视资源文件变化
+    hot_reload: Option<HotReload>,
+}
+
+impl AssetServer {
+    pub fn new(
+        device: Arc<Device>,
+        set_layout: Arc<DescriptorSetLayout>,
+        memory_allocator: Arc<StandardMemoryAllocator>,
+        set_allocator: Arc<StandardDescriptorSetAllocator>,
+    ) -> Self {
+        // 图集中相邻图片紧挨着，不能使用重复寻址
+        let sampler = Sampler::new(
+            device.clone(),
+            SamplerCreateInfo {
+                mag_filter: Filter::Linear,
+                min_filter: Filter::Linear,
+                address_mode: [SamplerAddressMode::ClampToEdge; 3],
+                ..SamplerCreateInfo::default()
+            },
+        ).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            textures: Assets::default(),
+            atlas: TextureAtlas::new(
+                ATLAS_PAGE_SIZE,
+                set_layout,
+                memory_allocator,
+                set_allocator,
+                sampler
+            ),
+            next_id: 0,
+            sender,
+            receiver,
+            hot_reload: if cfg!(debug_assertions) { HotReload::new() } else { None },
+        }
+    }
+
+    /// 开启或关闭热重载，调试构建下默认开启
+    pub fn set_hot_reload(&mut self, enabled: bool) {
+        if !enabled {
+            self.hot_reload = None;
+            return;
+        }
+
+        if self.hot_reload.is_some() {
+            return;
+        }
+
+        self.hot_reload = HotReload::new();
+
+        // 开启前已加载的纹理也需要监视
+        if let Some(hot_reload) = self.hot_reload.as_mut() {
+            for id in self.textures.ids() {
+                if let Some(path) = self.textures.path(id) {
+                    hot_reload.watch(path);
+                }
+            }
+        }
+    }
+
+    fn next_id(&mut self) -> AssetId {
+        self.next_id += 1;
+        AssetId(self.next_id)
+    }
+
+    /// 开始异步加载纹理并立即返回句柄，同一路径的纹理仍被持有时返回同一份
+    ///
+    /// 路径可以是 `assets://` 等虚拟路径，也可以是普通磁盘路径；
+    /// 解码在 tokio 的阻塞线程池中进行，没有运行时时退化为单独的线程
+    pub fn load_texture(&mut self, path: &str) -> Handle<Texture> {
+        if let Some(handle) = self.textures.find(path) {
+            return handle;
+        }
+
+        let id = self.next_id();
+        let handle = self.textures.reserve(id, Some(path.to_string()));
+
+        info!("loading: {}", path);
+
+        if let Some(hot_reload) = self.hot_reload.as_mut() {
+            hot_reload.watch(path);
+        }
+
+        self.spawn_decode(id, path.to_string(), false);
+
+        handle
+    }
+
+    /// 从内存中的图片数据（如模型内嵌的纹理）异步加载纹理，`key` 用于去重和日志
+    pub fn load_texture_from_memory(&mut self, key: &str, bytes: Vec<u8>) -> Handle<Texture> {
+        if let Some(handle) = self.textures.find(key) {
+            return handle;
+        }
+
+        let id = self.next_id();
+        let handle = self.textures.reserve(id, Some(key.to_string()));
+
+        info!("loading: {}", key);
+
+        let path = key.to_string();
+        self.spawn_task(move || Decoded {
+            id,
+            path,
+            result: decode_bytes(&bytes),
+            reload: false,
+        });
+
+        handle
+    }
+
+    /// 在后台线程解码图片
+    fn spawn_decode(&self, id: AssetId, path: String, reload: bool) {
+        self.spawn_task(move || {
+            let result = decode_image(&path);
+            Decoded { id, path, result, reload }
+        });
+    }
+
+    fn spawn_task(&self, decode: impl FnOnce() -> Decoded + Send + 'static) {
+        let sender = self.sender.clone();
+        let task = move || {
+            let _ = sender.send(decode());
+        };
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn_blocking(task);
+            }
+            Err(_) => {
+                std::thread::spawn(task);
+            }
+        }
+    }
+
+    /// 已加载完成的纹理，加载中、失败或已卸载时返回 None
+    pub fn texture(&self, handle: &Handle<Texture>) -> Option<&Texture> {
+        self.textures.get(handle)
+    }
+
+    pub fn load_state(&self, handle: &Handle<Texture>) -> LoadState {
+        self.textures.load_state(handle.id())
+    }
+
+    /// 纹理所在图集页的描述符集
+    pub fn page_set(&self, texture: &Texture) -> Arc<DescriptorSet> {
+        self.atlas.page_set(texture.region.page)
+    }
+
+    /// 立即卸载纹理，即使仍有句柄存活；之后这些句柄的状态为 Unloaded
+    pub fn unload(&mut self, handle: &Handle<Texture>) {
+        self.remove_texture(handle.id());
+    }
+
+    fn remove_texture(&mut self, id: AssetId) {
+        if let Some(path) = self.textures.path(id) {
+            info!("unloading: {}", path);
+        }
+
+        if let Some(texture) = self.textures.remove(id) {
+            self.atlas.remove(&texture.region);
+        }
+    }
+
+    /// 回收所有句柄都已被丢弃的资源，每帧调用
+    pub fn maintain(&mut self) {
+        for id in self.textures.unused() {
+            self.remove_texture(id);
+        }
+    }
+
+    /// 收取后台解码完成的图片并放入图集，每帧录制前调用
+    pub fn poll(&mut self, map: &mut ImageBufferManager) {
+        self.check_changed();
+
+        while let Ok(result) = self.receiver.try_recv() {
+            self.finish_load(result, map);
+        }
+    }
+
+    /// 阻塞直到所有正在加载的资源完成
+    pub fn wait_all(&mut self, map: &mut ImageBufferManager) {
+        while self.loading_count() > 0 {
+            match self.receiver.recv() {
+                Ok(result) => self.finish_load(result, map),
+                Err(_) => break,
+            }
+        }
+    }
+
+    fn loading_count(&self) -> usize {
+        self.textures.ids()
+            .filter(|id| self.textures.load_state(*id) == LoadState::Loading)
+            .count()
+    }
+
+    /// 文件被修改的纹理重新在后台解码
+    fn check_changed(&mut self) {
+        let Some(hot_reload) = self.hot_reload.as_mut() else {
+            return;
+        };
+
+        for path in hot_reload.changed() {
+            let Some(handle) = self.textures.find(&path) else {
+                continue;
+            };
+
+            info!("reloading: {}", path);
+            self.spawn_decode(handle.id(), path, true);
+        }
+    }
+
+    fn finish_load(&mut self, Decoded { id, path, result, reload }: Decoded, map: &mut ImageBufferManager) {
+        if reload {
+            self.finish_reload(id, path, result, map);
+            return;
+        }
+
+        // 加载期间句柄可能已被丢弃或卸载
+        if self.textures.load_state(id) != LoadState::Loading {
+            return;
+        }
+
+        match result {
+            Ok(img) if img.width() > 0 && img.height() > 0 => {
+                let texture = self.insert(&img, map);
+                self.textures.set_loaded(id, texture);
+            }
+            Ok(_) => {
+                error!("image is empty: {}", path);
+                self.textures.set_failed(id);
+            }
+            Err(e) => {
+                error!("failed to open image {}: {}", path, e);
+                self.textures.set_failed(id);
+            }
+        }
+    }
+
+    /// 替换已加载的纹理：尺寸不变时原地覆盖图集区域，否则重新放入图集
+    fn finish_reload(&mut self, id: AssetId, path: String, result: Result<RgbaImage, String>, map: &mut ImageBufferManager) {
+        let img = match result {
+            Ok(img) if img.width() > 0 && img.height() > 0 => img,
+            Ok(_) => {
+                error!("reloaded image is empty, keeping old texture: {}", path);
+                return;
+            }
+            Err(e) => {
+                // 文件可能还没写完，保留旧纹理等待下一次修改事件
+                error!("failed to reload image {}: {}", path, e);
+                return;
+            }
+        };
+
+        match self.textures.load_state(id) {
+            LoadState::Loaded => {
+                let old = *self.textures.get_by_id(id).unwrap();
+
+                if !self.atlas.update(&old.region, &img, map) {
+                    self.atlas.remove(&old.region);
+                    let texture = self.insert(&img, map);
+                    self.textures.set_loaded(id, texture);
+                }
+            }
+            // 之前加载失败的纹理在文件修复后恢复
+            LoadState::Failed => {
+                let texture = self.insert(&img, map);
+                self.textures.set_loaded(id, texture);
+            }
+            LoadState::Loading | LoadState::Unloaded => (),
+        }
+    }
+
+    fn insert(&mut self, img: &RgbaImage, map: &mut ImageBufferManager) -> Texture {
+        let (width, height) = img.dimensions();
+
+        Texture {
+            region: self.atlas.insert(img, map),
+            width,
+            height
+        }
+    }
+
+    /// 加载时把目录下的所有图片同步打包进图集，返回各图片的路径和句柄
+    ///
+    /// 按高度从大到小插入以减少图集空洞；句柄被丢弃后图片同样会被回收
+    pub fn pack_folder(&mut self, dir: &str, map: &mut ImageBufferManager) -> Vec<(String, Handle<Texture>)> {
+        let paths = match vfs::list(dir) {
+            Ok(paths) => paths,
+            Err(e) => {
+                error!("读取图片目录失败 {}: {}", dir, e);
+                return Vec::new();
+            }
+        };
+
+        let mut images = Vec::new();
+
+        for path in paths {
+            let is_image = Path::new(&path).extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+
+            if !is_image {
+                continue;
+            }
+
+            match decode_image(&path) {
+                Ok(img) => images.push((path, img)),
+                Err(e) => warn!("跳过无法打开的图片 {}: {}", path, e),
+            }
+        }
+
+        images.sort_by_key(|(_, img)| std::cmp::Reverse(img.height()));
+
+        let mut handles = Vec::new();
+
+        for (key, img) in images {
+            if img.width() == 0 || img.height() == 0 {
+                continue;
+            }
+
+            let handle = match self.textures.find(&key) {
+                Some(handle) if self.load_state(&handle) == LoadState::Loaded => {
+                    handles.push((key, handle));
+                    continue;
+                }
+                Some(handle) => handle,
+                None => {
+                    if let Some(hot_reload) = self.hot_reload.as_mut() {
+                        hot_reload.watch(&key);
+                    }
+
+                    let id = self.next_id();
+                    self.textures.reserve(id, Some(key.clone()))
+                }
+            };
+
+            let texture = self.insert(&img, map);
+            self.textures.set_loaded(handle.id(), texture);
+            handles.push((key, handle));
+        }
+
+        info!("已打包 {} 张图片到图集，共 {} 页: {}", handles.len(), self.atlas.page_count(), dir);
+
+        handles
+    }
+}
+
+/// 通过虚拟文件系统读取并解码图片
+fn decode_image(path: &str) -> Result<RgbaImage, String> {
+    let bytes = vfs::read(path).map_err(|e| e.to_string())?;
+    decode_bytes(&bytes)
+}
+
+fn decode_bytes(bytes: &[u8]) -> Result<RgbaImage, String> {
+    image::load_from_memory(bytes)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| e.to_string())
+}
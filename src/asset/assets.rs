@@ -0,0 +1,208 @@
+use crate::asset::handle::{AssetId, Handle, LoadState};
+use std::collections::HashMap;
+use std::sync::Weak;
+
+struct AssetSlot<T> {
+    path: Option<String>,
+    state: LoadState,
+    asset: Option<T>,
+    handle: Weak<AssetId>,
+}
+
+/// 同一类型资源的存储，按路径去重
+pub struct Assets<T> {
+    slots: HashMap<AssetId, AssetSlot<T>>,
+    paths: HashMap<String, AssetId>,
+}
+
+impl<T> Default for Assets<T> {
+    fn default() -> Self {
+        Self {
+            slots: HashMap::new(),
+            paths: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Assets<T> {
+    /// 路径对应的资源仍有句柄存活时返回该句柄
+    pub fn find(&self, path: &str) -> Option<Handle<T>> {
+        let id = self.paths.get(path)?;
+        let handle = self.slots.get(id)?.handle.upgrade()?;
+        Some(Handle::from_shared(handle))
+    }
+
+    /// 为即将加载的资源占位，状态为 Loading
+    pub fn reserve(&mut self, id: AssetId, path: Option<String>) -> Handle<T> {
+        let handle = Handle::new(id);
+
+        if let Some(path) = &path {
+            self.paths.insert(path.clone(), id);
+        }
+
+        self.slots.insert(id, AssetSlot {
+            path,
+            state: LoadState::Loading,
+            asset: None,
+            handle: handle.downgrade(),
+        });
+
+        handle
+    }
+
+    pub fn set_loaded(&mut self, id: AssetId, asset: T) {
+        if let Some(slot) = self.slots.get_mut(&id) {
+            slot.state = LoadState::Loaded;
+            slot.asset = Some(asset);
+        }
+    }
+
+    pub fn set_failed(&mut self, id: AssetId) {
+        if let Some(slot) = self.slots.get_mut(&id) {
+            slot.state = LoadState::Failed;
+            slot.asset = None;
+        }
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.slots.get(&handle.id())?.asset.as_ref()
+    }
+
+    pub fn get_by_id(&self, id: AssetId) -> Option<&T> {
+        self.slots.get(&id)?.asset.as_ref()
+    }
+
+    pub fn load_state(&self, id: AssetId) -> LoadState {
+        self.slots.get(&id).map_or(LoadState::Unloaded, |slot| slot.state)
+    }
+
+    pub fn path(&self, id: AssetId) -> Option<&str> {
+        self.slots.get(&id)?.path.as_deref()
+    }
+
+    /// 移除资源并返回其数据，之后该编号的状态为 Unloaded
+    pub fn remove(&mut self, id: AssetId) -> Option<T> {
+        let slot = self.slots.remove(&id)?;
+
+        if let Some(path) = &slot.path {
+            if self.paths.get(path) == Some(&id) {
+                self.paths.remove(path);
+            }
+        }
+
+        slot.asset
+    }
+
+    /// 所有句柄都已被丢弃的资源
+    pub fn unused(&self) -> Vec<AssetId> {
+        self.slots.iter()
+            .filter(|(_, slot)| slot.handle.strong_count() == 0)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = AssetId> + '_ {
+        self.slots.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_state_transitions() {
+        let mut assets = Assets::<u32>::default();
+        let id = AssetId(1);
+        assert_eq!(assets.load_state(id), LoadState::Unloaded);
+
+        let handle = assets.reserve(id, Some("a.png".to_string()));
+        assert_eq!(assets.load_state(id), LoadState::Loading);
+        assert_eq!(assets.get(&handle), None);
+
+        assets.set_loaded(id, 7);
+        assert_eq!(assets.load_state(id), LoadState::Loaded);
+        assert_eq!(assets.get(&handle), Some(&7));
+
+        // 重新加载失败时旧数据被清除
+        assets.set_failed(id);
+        assert_eq!(assets.load_state(id), LoadState::Failed);
+        assert_eq!(assets.get_by_id(id), None);
+
+        assets.set_loaded(id, 8);
+        assert_eq!(assets.remove(id), Some(8));
+        assert_eq!(assets.load_state(id), LoadState::Unloaded);
+        assert_eq!(assets.path(id), None);
+        assert!(assets.is_empty());
+    }
+
+    #[test]
+    fn unused_counts_live_handles() {
+        let mut assets = Assets::<u32>::default();
+        let a = assets.reserve(AssetId(1), None);
+        let b = assets.reserve(AssetId(2), None);
+        assets.set_loaded(AssetId(1), 1);
+        assert!(assets.unused().is_empty());
+
+        // 克隆出的句柄也要全部丢弃才算未使用
+        let a2 = a.clone();
+        drop(a);
+        assert!(assets.unused().is_empty());
+        drop(a2);
+        assert_eq!(assets.unused(), [AssetId(1)]);
+
+        drop(b);
+        let mut unused = assets.unused();
+        unused.sort();
+        assert_eq!(unused, [AssetId(1), AssetId(2)]);
+
+        // 未使用只是候选，回收前数据仍在
+        assert_eq!(assets.get_by_id(AssetId(1)), Some(&1));
+        assert_eq!(assets.len(), 2);
+    }
+
+    #[test]
+    fn find_dedups_by_path_while_handle_alive() {
+        let mut assets = Assets::<u32>::default();
+        assert!(assets.find("a.png").is_none());
+
+        let handle = assets.reserve(AssetId(1), Some("a.png".to_string()));
+        let found = assets.find("a.png").unwrap();
+        assert_eq!(found, handle);
+        assert!(assets.find("b.png").is_none());
+
+        // 最后一个句柄被丢弃后不再返回，即使数据尚未回收
+        drop(handle);
+        assert!(assets.find("a.png").is_some());
+        drop(found);
+        assert!(assets.find("a.png").is_none());
+        assert_eq!(assets.path(AssetId(1)), Some("a.png"));
+    }
+
+    #[test]
+    fn remove_keeps_path_of_newer_asset() {
+        let mut assets = Assets::<u32>::default();
+        let old = assets.reserve(AssetId(1), Some("a.png".to_string()));
+        drop(old);
+
+        // 旧资源的句柄已丢弃、尚未回收时，同一路径被重新加载为新编号
+        let new = assets.reserve(AssetId(2), Some("a.png".to_string()));
+        assets.set_loaded(AssetId(2), 2);
+
+        assert_eq!(assets.unused(), [AssetId(1)]);
+        assert_eq!(assets.remove(AssetId(1)), None);
+        assert_eq!(assets.find("a.png"), Some(new.clone()));
+
+        drop(new);
+        assert_eq!(assets.remove(AssetId(2)), Some(2));
+        assert!(assets.paths.is_empty());
+    }
+}
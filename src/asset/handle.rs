@@ -0,0 +1,81 @@
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Arc, Weak};
+
+/// 资源的唯一编号，不会被复用
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AssetId(pub u64);
+
+/// 资源加载状态
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LoadState {
+    /// 正在后台加载
+    Loading,
+    /// 已加载，可以使用
+    Loaded,
+    /// 打开或解码失败
+    Failed,
+    /// 已卸载或被回收
+    Unloaded,
+}
+
+/// 带类型的强引用资源句柄
+///
+/// 克隆句柄会增加引用计数，所有句柄都被丢弃后资源会在下一次 `AssetServer::maintain` 时回收
+pub struct Handle<T> {
+    id: Arc<AssetId>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub fn new(id: AssetId) -> Self {
+        Self {
+            id: Arc::new(id),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 由存储中的弱引用恢复出的句柄
+    pub fn from_shared(id: Arc<AssetId>) -> Self {
+        Self {
+            id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 不增加引用计数的弱引用，供资源存储判断句柄是否仍被持有
+    pub fn downgrade(&self) -> Weak<AssetId> {
+        Arc::downgrade(&self.id)
+    }
+
+    pub fn id(&self) -> AssetId {
+        *self.id
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self::from_shared(self.id.clone())
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Handle<{}>({})", std::any::type_name::<T>(), self.id.0)
+    }
+}
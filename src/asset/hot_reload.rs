@@ -0,0 +1,141 @@
+use crate::asset::vfs;
+use log::{error, info};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+/// 开发模式下的资源文件监视器，文件被覆盖后报告对应的虚拟路径
+pub struct HotReload {
+    watcher: RecommendedWatcher,
+    receiver: Receiver<PathBuf>,
+    /// 已监视的目录
+    dirs: HashSet<PathBuf>,
+    /// 磁盘路径到虚拟路径
+    files: HashMap<PathBuf, String>,
+}
+
+impl HotReload {
+    pub fn new() -> Option<Self> {
+        let (sender, receiver) = mpsc::channel();
+
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            match res {
+                Ok(event) if matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) => {
+                    for path in event.paths {
+                        let _ = sender.send(path);
+                    }
+                }
+                Ok(_) => (),
+                Err(e) => error!("资源监视出错: {}", e),
+            }
+        });
+
+        match watcher {
+            Ok(watcher) => {
+                info!("资源热重载已启用");
+                Some(Self {
+                    watcher,
+                    receiver,
+                    dirs: HashSet::new(),
+                    files: HashMap::new(),
+                })
+            }
+            Err(e) => {
+                error!("创建资源监视器失败，热重载不可用: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 监视虚拟路径对应的文件，资源包中的文件无法监视
+    pub fn watch(&mut self, uri: &str) {
+        let Some(path) = vfs::resolve(uri) else {
+            return;
+        };
+
+        let path = canonical(&path);
+
+        // 监视所在目录而不是文件本身，这样编辑器先删除再写入新文件时也能收到事件
+        if let Some(dir) = path.parent() {
+            if self.dirs.insert(dir.to_path_buf()) {
+                if let Err(e) = self.watcher.watch(dir, RecursiveMode::NonRecursive) {
+                    error!("监视目录失败 {}: {}", dir.display(), e);
+                    self.dirs.remove(dir);
+                }
+            }
+        }
+
+        self.files.insert(path, uri.to_string());
+    }
+
+    /// 不再报告该虚拟路径的修改，所在目录仍保持监视
+    pub fn unwatch(&mut self, uri: &str) {
+        self.files.retain(|_, watched| watched != uri);
+    }
+
+    /// 自上次调用以来被修改过的资源虚拟路径，同一文件的多次事件合并为一次
+    pub fn changed(&mut self) -> Vec<String> {
+        let mut changed = HashSet::new();
+
+        while let Ok(path) = self.receiver.try_recv() {
+            if let Some(uri) = self.files.get(&canonical(&path)) {
+                changed.insert(uri.clone());
+            }
+        }
+
+        changed.into_iter().collect()
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::vfs::DirectorySource;
+    use std::time::{Duration, Instant};
+
+    /// 文件事件是异步送达的，轮询到有结果或超时为止
+    fn wait_changed(hot_reload: &mut HotReload) -> Vec<String> {
+        let start = Instant::now();
+
+        loop {
+            let changed = hot_reload.changed();
+            if !changed.is_empty() || start.elapsed() > Duration::from_secs(5) {
+                return changed;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn modified_file_is_reported_by_uri() {
+        let dir = std::env::temp_dir().join(format!("azer-hot-reload-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("watched.txt"), b"1").unwrap();
+        std::fs::write(dir.join("other.txt"), b"1").unwrap();
+
+        vfs::mount("hot-reload-test", Box::new(DirectorySource::new(dir.clone())));
+
+        let mut hot_reload = HotReload::new().unwrap();
+        hot_reload.watch("hot-reload-test://watched.txt");
+        assert!(hot_reload.changed().is_empty());
+
+        // 同目录下未监视的文件不报告
+        std::fs::write(dir.join("other.txt"), b"2").unwrap();
+        std::fs::write(dir.join("watched.txt"), b"2").unwrap();
+        assert_eq!(wait_changed(&mut hot_reload), ["hot-reload-test://watched.txt"]);
+
+        hot_reload.unwatch("hot-reload-test://watched.txt");
+        std::fs::write(dir.join("watched.txt"), b"3").unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(hot_reload.changed().is_empty());
+
+        vfs::unmount("hot-reload-test");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
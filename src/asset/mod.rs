@@ -0,0 +1,8 @@
+pub mod handle;
+pub mod assets;
+pub mod asset_server;
+pub mod texture;
+pub mod vfs;
+pub mod hot_reload;
+pub mod model;
+pub mod obj;
\ No newline at end of file
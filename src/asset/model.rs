@@ -0,0 +1,308 @@
+use crate::asset::asset_server::AssetServer;
+use crate::asset::handle::Handle;
+use crate::asset::texture::Texture;
+use crate::asset::vfs;
+use crate::renderer::mesh_buffer::MeshHandle;
+use crate::renderer::renderer3d::render_mesh::Renderer3D;
+use crate::renderer::shapes::mesh::{Mesh3D, Vertex3D};
+use base64::Engine;
+use glam::{Mat4, Vec3};
+use gltf::mesh::Mode;
+use gltf::Gltf;
+use log::{info, warn};
+
+/// 模型材质：基础颜色和基础颜色纹理
+///
+/// 纹理放入图集，UV 超出 [0, 1] 的平铺纹理会采样到相邻图片
+#[derive(Clone, Debug)]
+pub struct ModelMaterial {
+    pub name: Option<String>,
+    pub base_color: [f32; 4],
+    pub texture: Option<Handle<Texture>>,
+}
+
+impl Default for ModelMaterial {
+    fn default() -> Self {
+        Self {
+            name: None,
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            texture: None,
+        }
+    }
+}
+
+/// 使用同一材质的一段网格
+#[derive(Copy, Clone, Debug)]
+pub struct ModelPrimitive {
+    pub mesh: MeshHandle,
+    /// `Model::materials` 中的下标，None 时使用默认材质
+    pub material: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ModelMesh {
+    pub name: Option<String>,
+    pub primitives: Vec<ModelPrimitive>,
+}
+
+/// 节点层级中的一个节点
+#[derive(Clone, Debug)]
+pub struct ModelNode {
+    pub name: Option<String>,
+    /// 相对父节点的变换
+    pub transform: Mat4,
+    /// `Model::meshes` 中的下标
+    pub mesh: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// 导入的模型，网格已上传到 `Renderer3D`，纹理通过 `AssetServer` 异步加载
+#[derive(Clone, Debug, Default)]
+pub struct Model {
+    pub meshes: Vec<ModelMesh>,
+    pub materials: Vec<ModelMaterial>,
+    pub nodes: Vec<ModelNode>,
+    /// 场景的根节点
+    pub roots: Vec<usize>,
+}
+
+impl Model {
+    /// 按层级展开所有带网格的节点，返回（世界变换，网格下标）
+    ///
+    /// 每个节点只展开一次，损坏文件中的循环引用会被忽略
+    pub fn mesh_instances(&self, transform: Mat4) -> Vec<(Mat4, usize)> {
+        let mut instances = Vec::new();
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack: Vec<(usize, Mat4)> = self.roots.iter().map(|root| (*root, transform)).collect();
+
+        while let Some((index, parent)) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else { continue };
+            if std::mem::replace(&mut visited[index], true) {
+                warn!("模型节点 {} 被重复引用，已跳过", index);
+                continue;
+            }
+
+            let world = parent * node.transform;
+
+            if let Some(mesh) = node.mesh {
+                instances.push((world, mesh));
+            }
+
+            stack.extend(node.children.iter().map(|child| (*child, world)));
+        }
+
+        instances
+    }
+}
+
+/// 导入 `.gltf` / `.glb` 模型
+///
+/// 路径经过虚拟文件系统解析，外部缓冲和纹理相对模型文件查找；
+/// 只导入三角形图元，缺少法线时按面法线平滑生成
+pub fn load_gltf(path: &str, renderer3d: &mut Renderer3D, assets: &mut AssetServer) -> Result<Model, String> {
+    let bytes = vfs::read(path).map_err(|e| format!("读取模型失败 {}: {}", path, e))?;
+    let gltf = Gltf::from_slice(&bytes).map_err(|e| format!("解析模型失败 {}: {}", path, e))?;
+
+    let buffers = gltf.document.buffers()
+        .map(|buffer| match buffer.source() {
+            gltf::buffer::Source::Bin => gltf.blob.clone()
+                .ok_or_else(|| format!("模型缺少 GLB 二进制块: {}", path)),
+            gltf::buffer::Source::Uri(uri) => read_uri(path, uri),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 截断的文件中缓冲视图可能超出缓冲末尾，读取访问器和图片前先检查
+    for view in gltf.document.views() {
+        let end = view.offset().checked_add(view.length());
+        if end.is_none_or(|end| end > buffers[view.buffer().index()].len()) {
+            return Err(format!("模型缓冲视图 {} 超出缓冲范围: {}", view.index(), path));
+        }
+    }
+
+    let textures = gltf.document.images()
+        .map(|image| match image.source() {
+            gltf::image::Source::View { view, .. } => {
+                let data = &buffers[view.buffer().index()];
+                let bytes = data.get(view.offset()..view.offset() + view.length())
+                    .ok_or_else(|| format!("模型图片超出缓冲范围 {}: image{}", path, image.index()))?
+                    .to_vec();
+                Ok(assets.load_texture_from_memory(&format!("{}#image{}", path, image.index()), bytes))
+            }
+            gltf::image::Source::Uri { uri, .. } => match decode_data_uri(uri) {
+                Some(bytes) => Ok(assets.load_texture_from_memory(&format!("{}#image{}", path, image.index()), bytes?)),
+                None => Ok(assets.load_texture(&vfs::sibling(path, uri))),
+            },
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let materials = gltf.document.materials()
+        .map(|material| {
+            let pbr = material.pbr_metallic_roughness();
+
+            ModelMaterial {
+                name: material.name().map(str::to_string),
+                base_color: pbr.base_color_factor(),
+                texture: pbr.base_color_texture()
+                    .map(|info| textures[info.texture().source().index()].clone()),
+            }
+        })
+        .collect();
+
+    let mut meshes = Vec::new();
+
+    for mesh in gltf.document.meshes() {
+        let mut primitives = Vec::new();
+
+        for primitive in mesh.primitives() {
+            if primitive.mode() != Mode::Triangles {
+                warn!("跳过非三角形图元 {:?}: {}", primitive.mode(), path);
+                continue;
+            }
+
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+
+            let Some(positions) = reader.read_positions() else {
+                warn!("跳过没有顶点位置的图元: {}", path);
+                continue;
+            };
+            let positions: Vec<[f32; 3]> = positions.collect();
+
+            let indices: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+
+            let normals: Vec<[f32; 3]> = match reader.read_normals() {
+                Some(normals) => normals.collect(),
+                None => smooth_normals(&positions, &indices),
+            };
+
+            let uvs: Vec<[f32; 2]> = match reader.read_tex_coords(0) {
+                Some(uvs) => uvs.into_f32().collect(),
+                None => vec![[0.0, 0.0]; positions.len()],
+            };
+
+            let colors: Vec<[f32; 4]> = match reader.read_colors(0) {
+                Some(colors) => colors.into_rgba_f32().collect(),
+                None => vec![[1.0, 1.0, 1.0, 1.0]; positions.len()],
+            };
+
+            let vertices = positions.iter()
+                .enumerate()
+                .map(|(i, position)| Vertex3D {
+                    position: *position,
+                    normal: normals.get(i).copied().unwrap_or([0.0, 1.0, 0.0]),
+                    uv: uvs.get(i).copied().unwrap_or([0.0, 0.0]),
+                    color: colors.get(i).copied().unwrap_or([1.0, 1.0, 1.0, 1.0]),
+                })
+                .collect();
+
+            primitives.push(ModelPrimitive {
+                mesh: renderer3d.upload_mesh(&Mesh3D { vertices, indices }),
+                material: primitive.material().index(),
+            });
+        }
+
+        meshes.push(ModelMesh {
+            name: mesh.name().map(str::to_string),
+            primitives,
+        });
+    }
+
+    let nodes: Vec<ModelNode> = gltf.document.nodes()
+        .map(|node| ModelNode {
+            name: node.name().map(str::to_string),
+            transform: Mat4::from_cols_array_2d(&node.transform().matrix()),
+            mesh: node.mesh().map(|mesh| mesh.index()),
+            children: node.children().map(|child| child.index()).collect(),
+        })
+        .collect();
+
+    let roots = match gltf.document.default_scene().or_else(|| gltf.document.scenes().next()) {
+        Some(scene) => scene.nodes().map(|node| node.index()).collect(),
+        // 没有场景时，不是任何节点子节点的节点作为根
+        None => (0..nodes.len())
+            .filter(|i| !nodes.iter().any(|node| node.children.contains(i)))
+            .collect(),
+    };
+
+    info!("已导入模型 {}: {} 个网格, {} 个材质, {} 个节点", path, meshes.len(), materials.len(), nodes.len());
+
+    Ok(Model {
+        meshes,
+        materials,
+        nodes,
+        roots,
+    })
+}
+
+/// 读取外部缓冲或 data URI
+fn read_uri(path: &str, uri: &str) -> Result<Vec<u8>, String> {
+    match decode_data_uri(uri) {
+        Some(bytes) => bytes,
+        None => {
+            let uri = vfs::sibling(path, uri);
+            vfs::read(&uri).map_err(|e| format!("读取模型缓冲失败 {}: {}", uri, e))
+        }
+    }
+}
+
+/// 解码 `data:...;base64,` 形式的内嵌数据，不是 data URI 时返回 None
+fn decode_data_uri(uri: &str) -> Option<Result<Vec<u8>, String>> {
+    let data = uri.strip_prefix("data:")?;
+
+    Some(match data.split_once(";base64,") {
+        Some((_, encoded)) => base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("解码 data URI 失败: {}", e)),
+        None => Err("只支持 base64 编码的 data URI".to_string()),
+    })
+}
+
+/// 累加相邻三角形的面法线得到平滑的顶点法线
+pub fn smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| i as usize);
+        if a >= positions.len() || b >= positions.len() || c >= positions.len() {
+            continue;
+        }
+
+        let (pa, pb, pc) = (Vec3::from(positions[a]), Vec3::from(positions[b]), Vec3::from(positions[c]));
+        // 未归一化的叉积按面积加权
+        let normal = (pb - pa).cross(pc - pa);
+
+        normals[a] += normal;
+        normals[b] += normal;
+        normals[c] += normal;
+    }
+
+    normals.into_iter()
+        .map(|normal| normal.normalize_or(Vec3::Y).to_array())
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_normals_of_quad() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+        let normals = smooth_normals(&positions, &[0, 1, 2, 0, 2, 3]);
+
+        assert_eq!(normals, vec![[0.0, 0.0, 1.0]; 4]);
+    }
+
+    #[test]
+    fn smooth_normals_ignores_bad_indices() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [5.0, 5.0, 5.0]];
+        // 越界的三角形被跳过，未被引用的顶点使用默认的 +Y
+        let normals = smooth_normals(&positions, &[0, 1, 2, 0, 1, 9]);
+
+        assert_eq!(normals[..3], [[0.0, 0.0, 1.0]; 3]);
+        assert_eq!(normals[3], [0.0, 1.0, 0.0]);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::asset::asset_server::AssetServer;
+use crate::asset::model::{smooth_normals, Model, ModelMaterial, ModelMesh, ModelNode, ModelPrimitive};
+use crate::asset::vfs;
+use crate::renderer::renderer3d::render_mesh::Renderer3D;
+use crate::renderer::shapes::mesh::{Mesh3D, Vertex3D};
+use glam::Mat4;
+use log::{info, warn};
+use std::io::{BufReader, Cursor};
+
+/// 导入 Wavefront `.obj` 模型及其 `.mtl` 材质
+///
+/// 多边形三角化，相同的位置/法线/UV 组合合并为一个顶点；
+/// 每个 `o` / `g` 对象成为一个根节点，材质只使用漫反射颜色和漫反射纹理
+pub fn load_obj(path: &str, renderer3d: &mut Renderer3D, assets: &mut AssetServer) -> Result<Model, String> {
+    let (objects, materials) = read_obj(path)?;
+
+    let materials = materials.into_iter()
+        .map(|material| {
+            let [r, g, b] = material.diffuse.unwrap_or([1.0, 1.0, 1.0]);
+
+            ModelMaterial {
+                base_color: [r, g, b, material.dissolve.unwrap_or(1.0)],
+                texture: material.diffuse_texture
+                    .map(|texture| assets.load_texture(&vfs::sibling(path, &texture))),
+                name: Some(material.name),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut meshes = Vec::new();
+    let mut nodes = Vec::new();
+
+    for object in objects {
+        let handle = renderer3d.upload_mesh(&object.mesh);
+
+        nodes.push(ModelNode {
+            name: Some(object.name.clone()),
+            transform: Mat4::IDENTITY,
+            mesh: Some(meshes.len()),
+            children: Vec::new(),
+        });
+
+        meshes.push(ModelMesh {
+            name: Some(object.name),
+            primitives: vec![ModelPrimitive {
+                mesh: handle,
+                material: object.material.filter(|id| *id < materials.len()),
+            }],
+        });
+    }
+
+    info!("已导入模型 {}: {} 个网格, {} 个材质", path, meshes.len(), materials.len());
+
+    Ok(Model {
+        meshes,
+        materials,
+        roots: (0..nodes.len()).collect(),
+        nodes,
+    })
+}
+
+/// OBJ 中的一个对象，已转换为引擎的顶点格式
+struct ObjMesh {
+    name: String,
+    mesh: Mesh3D,
+    /// tobj 返回的材质下标
+    material: Option<usize>,
+}
+
+/// 解析模型和材质，不创建 GPU 资源；空对象被跳过
+fn read_obj(path: &str) -> Result<(Vec<ObjMesh>, Vec<tobj::Material>), String> {
+    let bytes = vfs::read(path).map_err(|e| format!("读取模型失败 {}: {}", path, e))?;
+
+    let options = tobj::LoadOptions {
+        triangulate: true,
+        single_index: true,
+        ..tobj::LoadOptions::default()
+    };
+
+    let (models, materials) = tobj::load_obj_buf(&mut BufReader::new(Cursor::new(bytes)), &options, |mtl_path| {
+        let uri = vfs::sibling(path, &mtl_path.to_string_lossy());
+        let bytes = vfs::read(&uri).map_err(|e| {
+            warn!("读取材质失败 {}: {}", uri, e);
+            tobj::LoadError::OpenFileFailed
+        })?;
+
+        tobj::load_mtl_buf(&mut BufReader::new(Cursor::new(bytes)))
+    }).map_err(|e| format!("解析模型失败 {}: {}", path, e))?;
+
+    // 材质缺失时仍导入几何体
+    let materials = materials.unwrap_or_else(|e| {
+        warn!("加载模型材质失败 {}: {}", path, e);
+        Vec::new()
+    });
+
+    let objects = models.into_iter()
+        .filter(|model| !model.mesh.positions.is_empty() && !model.mesh.indices.is_empty())
+        .map(|model| {
+            let mesh = model.mesh;
+
+            let positions: Vec<[f32; 3]> = mesh.positions.chunks_exact(3)
+                .map(|p| [p[0], p[1], p[2]])
+                .collect();
+
+            let normals: Vec<[f32; 3]> = if mesh.normals.is_empty() {
+                smooth_normals(&positions, &mesh.indices)
+            } else {
+                mesh.normals.chunks_exact(3).map(|n| [n[0], n[1], n[2]]).collect()
+            };
+
+            // OBJ 的 v 轴向上，纹理坐标原点在左下角
+            let uvs: Vec<[f32; 2]> = mesh.texcoords.chunks_exact(2)
+                .map(|t| [t[0], 1.0 - t[1]])
+                .collect();
+
+            let colors: Vec<[f32; 4]> = mesh.vertex_color.chunks_exact(3)
+                .map(|c| [c[0], c[1], c[2], 1.0])
+                .collect();
+
+            let vertices = positions.iter()
+                .enumerate()
+                .map(|(i, position)| Vertex3D {
+                    position: *position,
+                    normal: normals.get(i).copied().unwrap_or([0.0, 1.0, 0.0]),
+                    uv: uvs.get(i).copied().unwrap_or([0.0, 0.0]),
+                    color: colors.get(i).copied().unwrap_or([1.0, 1.0, 1.0, 1.0]),
+                })
+                .collect();
+
+            ObjMesh {
+                name: model.name,
+                mesh: Mesh3D {
+                    vertices,
+                    indices: mesh.indices,
+                },
+                material: mesh.material_id,
+            }
+        })
+        .collect();
+
+    Ok((objects, materials))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::vfs::DirectorySource;
+
+    const QUAD_OBJ: &str = "\
+mtllib quad.mtl
+o quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+usemtl red
+f 1/1 2/2 3/3 4/4
+";
+
+    const QUAD_MTL: &str = "\
+newmtl red
+Kd 1 0 0
+d 0.5
+map_Kd red.png
+";
+
+    /// 把文件写入临时目录并挂载，每个测试使用独立的挂载名
+    fn mount_fixture(mount: &str, files: &[(&str, &str)]) {
+        let dir = std::env::temp_dir().join(format!("azer-obj-{}-{}", mount, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (name, contents) in files {
+            std::fs::write(dir.join(name), contents).unwrap();
+        }
+
+        vfs::mount(mount, Box::new(DirectorySource::new(dir)));
+    }
+
+    #[test]
+    fn quad_is_triangulated_and_deduplicated() {
+        mount_fixture("obj-quad", &[("quad.obj", QUAD_OBJ), ("quad.mtl", QUAD_MTL)]);
+
+        let (objects, materials) = read_obj("obj-quad://quad.obj").unwrap();
+        assert_eq!(objects.len(), 1);
+
+        let quad = &objects[0];
+        assert_eq!(quad.name, "quad");
+        // 四边形拆成两个三角形，共用的角只保留一个顶点
+        assert_eq!(quad.mesh.indices.len(), 6);
+        assert_eq!(quad.mesh.vertices.len(), 4);
+
+        // 没有法线时按面法线生成
+        for vertex in &quad.mesh.vertices {
+            assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+        }
+
+        assert_eq!(quad.material, Some(0));
+        assert_eq!(materials.len(), 1);
+        assert_eq!(materials[0].name, "red");
+        assert_eq!(materials[0].diffuse, Some([1.0, 0.0, 0.0]));
+        assert_eq!(materials[0].diffuse_texture.as_deref(), Some("red.png"));
+
+        vfs::unmount("obj-quad");
+    }
+
+    #[test]
+    fn texture_v_is_flipped() {
+        mount_fixture("obj-uv", &[("quad.obj", QUAD_OBJ), ("quad.mtl", QUAD_MTL)]);
+
+        let (objects, _) = read_obj("obj-uv://quad.obj").unwrap();
+
+        for vertex in &objects[0].mesh.vertices {
+            // 位置与纹理坐标相同，翻转后 v = 1 - y
+            let [x, y, _] = vertex.position;
+            assert_eq!(vertex.uv, [x, 1.0 - y]);
+        }
+
+        vfs::unmount("obj-uv");
+    }
+
+    #[test]
+    fn missing_mtl_still_loads_geometry() {
+        mount_fixture("obj-no-mtl", &[("quad.obj", QUAD_OBJ)]);
+
+        let (objects, materials) = read_obj("obj-no-mtl://quad.obj").unwrap();
+
+        assert!(materials.is_empty());
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].mesh.vertices.len(), 4);
+        assert_eq!(objects[0].material, None);
+
+        vfs::unmount("obj-no-mtl");
+    }
+
+    #[test]
+    fn missing_obj_is_an_error() {
+        mount_fixture("obj-missing", &[]);
+
+        assert!(read_obj("obj-missing://missing.obj").is_err());
+
+        vfs::unmount("obj-missing");
+    }
+}
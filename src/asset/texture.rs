@@ -0,0 +1,9 @@
+use crate::renderer::renderer2d::texture_atlas::AtlasRegion;
+
+/// 已放入纹理图集的图片
+#[derive(Copy, Clone, Debug)]
+pub struct Texture {
+    pub region: AtlasRegion,
+    pub width: u32,
+    pub height: u32,
+}
@@ -0,0 +1,644 @@
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+/// 默认的资源挂载名，对应 `assets://` 前缀
+pub const ASSETS_MOUNT: &str = "assets";
+
+/// 发布版本使用的资源包文件名，放在可执行文件旁
+pub const ASSETS_PACK_NAME: &str = "assets.pak";
+
+const PACK_MAGIC: &[u8; 4] = b"AZPK";
+const PACK_VERSION: u32 = 1;
+/// 路径为空时一个条目索引的长度：路径长度 u32 + 偏移、大小、原始大小 u64 + 压缩标记 u8
+const PACK_ENTRY_MIN_SIZE: u64 = 4 + 8 * 3 + 1;
+
+static VFS: RwLock<Vfs> = RwLock::new(Vfs::new());
+
+/// 挂载点背后的数据来源
+pub trait MountSource: Send + Sync {
+    /// 读取挂载点内相对路径对应的文件，路径分隔符为 `/`
+    fn read(&self, path: &str) -> io::Result<Vec<u8>>;
+
+    /// 列出目录下的文件（不递归），返回挂载点内的相对路径
+    fn list(&self, dir: &str) -> io::Result<Vec<String>>;
+
+    /// 对应的磁盘路径，资源包等没有实际文件的来源返回 None
+    fn resolve(&self, _path: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// 开发时使用：直接映射到磁盘目录
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into()
+        }
+    }
+}
+
+impl MountSource for DirectorySource {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        std::fs::read(self.root.join(path))
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let mut files = Vec::new();
+
+        for entry in std::fs::read_dir(self.root.join(dir))? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.push(join(dir, &entry.file_name().to_string_lossy()));
+            }
+        }
+
+        Ok(files)
+    }
+
+    fn resolve(&self, path: &str) -> Option<PathBuf> {
+        Some(self.root.join(path))
+    }
+}
+
+struct PackEntry {
+    offset: u64,
+    size: u64,
+    raw_size: u64,
+    compressed: bool,
+}
+
+/// 发布时使用：单个资源包文件，头部为索引，条目可选 deflate 压缩
+///
+/// 格式：`AZPK` | 版本 u32 | 条目数 u32 | 条目索引 | 数据，数值均为小端
+pub struct PackSource {
+    file: Mutex<File>,
+    entries: HashMap<String, PackEntry>,
+}
+
+impl PackSource {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = File::open(path.as_ref())?;
+
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)?;
+        if &magic != PACK_MAGIC {
+            return Err(invalid_pack("不是有效的资源包".to_string()));
+        }
+
+        let version = read_u32(&mut file)?;
+        if version != PACK_VERSION {
+            return Err(invalid_pack(format!("不支持的资源包版本: {version}")));
+        }
+
+        // 索引中的长度和偏移都来自文件，分配内存前先与文件长度比较，避免损坏的资源包导致超大分配
+        let len = file.metadata()?.len();
+
+        let count = read_u32(&mut file)?;
+        if 12 + count as u64 * PACK_ENTRY_MIN_SIZE > len {
+            return Err(invalid_pack(format!("条目数超出文件长度: {count}")));
+        }
+        let mut entries = HashMap::with_capacity(count as usize);
+
+        for _ in 0..count {
+            let path_len = read_u32(&mut file)? as u64;
+            if file.stream_position()? + path_len > len {
+                return Err(invalid_pack(format!("路径长度超出文件长度: {path_len}")));
+            }
+            let mut path = vec![0u8; path_len as usize];
+            file.read_exact(&mut path)?;
+            let path = String::from_utf8(path)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let offset = read_u64(&mut file)?;
+            let size = read_u64(&mut file)?;
+            let raw_size = read_u64(&mut file)?;
+            let mut compressed = [0u8; 1];
+            file.read_exact(&mut compressed)?;
+
+            if offset.checked_add(size).is_none_or(|end| end > len) {
+                return Err(invalid_pack(format!("{path} 的数据超出文件长度")));
+            }
+            if compressed[0] == 0 && raw_size != size {
+                return Err(invalid_pack(format!("{path} 未压缩但大小不一致")));
+            }
+
+            entries.insert(path, PackEntry {
+                offset,
+                size,
+                raw_size,
+                compressed: compressed[0] != 0,
+            });
+        }
+
+        info!("打开资源包 {}: {} 个文件", path.as_ref().display(), entries.len());
+
+        Ok(Self {
+            file: Mutex::new(file),
+            entries,
+        })
+    }
+}
+
+impl MountSource for PackSource {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        let entry = self.entries.get(path)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("资源包中没有 {path}")))?;
+
+        let mut data = vec![0u8; entry.size as usize];
+        {
+            let mut file = self.file.lock().unwrap();
+            file.seek(SeekFrom::Start(entry.offset))?;
+            file.read_exact(&mut data)?;
+        }
+
+        if !entry.compressed {
+            return Ok(data);
+        }
+
+        // raw_size 未经校验，只解压到该长度，不按它预先分配
+        let mut raw = Vec::new();
+        DeflateDecoder::new(data.as_slice()).take(entry.raw_size).read_to_end(&mut raw)?;
+        if raw.len() as u64 != entry.raw_size {
+            return Err(invalid_pack(format!("{path} 解压后大小不一致")));
+        }
+        Ok(raw)
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        let prefix = if dir.is_empty() { String::new() } else { format!("{}/", dir.trim_end_matches('/')) };
+
+        Ok(self.entries.keys()
+            .filter(|path| path.strip_prefix(&prefix).is_some_and(|rest| !rest.contains('/')))
+            .cloned()
+            .collect())
+    }
+}
+
+/// 挂载表：把 `name://path` 形式的路径解析到对应的数据来源
+pub struct Vfs {
+    mounts: Vec<(String, Box<dyn MountSource>)>,
+}
+
+impl Default for Vfs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vfs {
+    pub const fn new() -> Self {
+        Self {
+            mounts: Vec::new()
+        }
+    }
+
+    /// 挂载数据来源，同名挂载会被替换
+    pub fn mount(&mut self, name: &str, source: Box<dyn MountSource>) {
+        self.unmount(name);
+        self.mounts.push((name.to_string(), source));
+    }
+
+    pub fn unmount(&mut self, name: &str) {
+        self.mounts.retain(|(mount, _)| mount != name);
+    }
+
+    pub fn is_mounted(&self, name: &str) -> bool {
+        self.mounts.iter().any(|(mount, _)| mount == name)
+    }
+
+    /// 读取文件；不带 `name://` 前缀的路径按普通磁盘路径读取
+    pub fn read(&self, uri: &str) -> io::Result<Vec<u8>> {
+        match split_uri(uri) {
+            Some(split) => {
+                let (name, path) = split?;
+                self.source(name)?.read(&path)
+            }
+            None => std::fs::read(uri),
+        }
+    }
+
+    /// 列出目录下的文件，返回值与参数使用相同的前缀形式
+    pub fn list(&self, dir_uri: &str) -> io::Result<Vec<String>> {
+        match split_uri(dir_uri) {
+            Some(split) => {
+                let (name, dir) = split?;
+                Ok(self.source(name)?
+                    .list(&dir)?
+                    .into_iter()
+                    .map(|path| format!("{name}://{path}"))
+                    .collect())
+            }
+            None => {
+                let mut files = Vec::new();
+                for entry in std::fs::read_dir(dir_uri)? {
+                    let entry = entry?;
+                    if entry.file_type()?.is_file() {
+                        files.push(entry.path().to_string_lossy().to_string());
+                    }
+                }
+                Ok(files)
+            }
+        }
+    }
+
+    /// 虚拟路径对应的磁盘路径，用于监视文件变化
+    pub fn resolve(&self, uri: &str) -> Option<PathBuf> {
+        match split_uri(uri) {
+            Some(split) => {
+                let (name, path) = split.ok()?;
+                self.source(name).ok()?.resolve(&path)
+            }
+            None => Some(PathBuf::from(uri)),
+        }
+    }
+
+    fn source(&self, name: &str) -> io::Result<&dyn MountSource> {
+        self.mounts.iter()
+            .find(|(mount, _)| mount == name)
+            .map(|(_, source)| source.as_ref())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("未挂载: {name}://")))
+    }
+}
+
+/// 在全局挂载表中挂载数据来源
+pub fn mount(name: &str, source: Box<dyn MountSource>) {
+    VFS.write().unwrap().mount(name, source);
+}
+
+pub fn unmount(name: &str) {
+    VFS.write().unwrap().unmount(name);
+}
+
+/// 通过全局挂载表读取文件
+pub fn read(uri: &str) -> io::Result<Vec<u8>> {
+    VFS.read().unwrap().read(uri)
+}
+
+/// 通过全局挂载表列出目录
+pub fn list(dir_uri: &str) -> io::Result<Vec<String>> {
+    VFS.read().unwrap().list(dir_uri)
+}
+
+/// 通过全局挂载表解析磁盘路径
+pub fn resolve(uri: &str) -> Option<PathBuf> {
+    VFS.read().unwrap().resolve(uri)
+}
+
+/// 未手动挂载 `assets://` 时自动挂载：
+/// 可执行文件旁存在 `assets.pak` 时使用资源包，否则依次查找可执行文件旁的 `assets` 目录
+/// 和工作目录下的 `src/assets` 目录；调试构建最后回退到本工程的 `src/assets`
+pub fn mount_default_assets() {
+    if VFS.read().unwrap().is_mounted(ASSETS_MOUNT) {
+        return;
+    }
+
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+
+    let pack = exe_dir.as_ref()
+        .map(|dir| dir.join(ASSETS_PACK_NAME))
+        .filter(|path| path.exists());
+
+    if let Some(pack) = pack {
+        match PackSource::open(&pack) {
+            Ok(source) => {
+                mount(ASSETS_MOUNT, Box::new(source));
+                return;
+            }
+            Err(e) => warn!("打开资源包失败 {}: {}，改用资源目录", pack.display(), e),
+        }
+    }
+
+    let mut candidates: Vec<PathBuf> = exe_dir.iter().map(|dir| dir.join("assets")).collect();
+    candidates.push(Path::new("src").join("assets"));
+    // 只在调试构建中编入构建机上的工程路径，方便从其他目录运行
+    #[cfg(debug_assertions)]
+    candidates.push(Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("assets"));
+
+    match candidates.into_iter().find(|dir| dir.is_dir()) {
+        Some(dir) => {
+            info!("挂载资源目录: {}", dir.display());
+            mount(ASSETS_MOUNT, Box::new(DirectorySource::new(dir)));
+        }
+        None => warn!("没有找到资源包或资源目录，assets:// 未挂载"),
+    }
+}
+
+/// 把目录递归打包为资源包，返回打包的文件数
+///
+/// `compress` 为 true 时对每个文件尝试 deflate 压缩，压缩后更大的文件按原样存储
+pub fn write_pack(src_dir: impl AsRef<Path>, out_path: impl AsRef<Path>, compress: bool) -> io::Result<usize> {
+    let mut files = Vec::new();
+    collect_files(src_dir.as_ref(), "", &mut files)?;
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut blobs = Vec::with_capacity(files.len());
+    for (path, data) in files {
+        let raw_size = data.len() as u64;
+
+        let (data, compressed) = if compress {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&data)?;
+            let packed = encoder.finish()?;
+
+            if packed.len() < data.len() { (packed, true) } else { (data, false) }
+        } else {
+            (data, false)
+        };
+
+        blobs.push((path, data, raw_size, compressed));
+    }
+
+    // 头部 + 每个条目的索引长度
+    let index_size: u64 = 12 + blobs.iter()
+        .map(|(path, ..)| PACK_ENTRY_MIN_SIZE + path.len() as u64)
+        .sum::<u64>();
+
+    let mut out = io::BufWriter::new(File::create(out_path.as_ref())?);
+    out.write_all(PACK_MAGIC)?;
+    out.write_all(&PACK_VERSION.to_le_bytes())?;
+    out.write_all(&(blobs.len() as u32).to_le_bytes())?;
+
+    let mut offset = index_size;
+    for (path, data, raw_size, compressed) in blobs.iter() {
+        out.write_all(&(path.len() as u32).to_le_bytes())?;
+        out.write_all(path.as_bytes())?;
+        out.write_all(&offset.to_le_bytes())?;
+        out.write_all(&(data.len() as u64).to_le_bytes())?;
+        out.write_all(&raw_size.to_le_bytes())?;
+        out.write_all(&[*compressed as u8])?;
+        offset += data.len() as u64;
+    }
+
+    for (_, data, ..) in blobs.iter() {
+        out.write_all(data)?;
+    }
+
+    out.flush()?;
+
+    info!("已写出资源包 {}: {} 个文件", out_path.as_ref().display(), blobs.len());
+
+    Ok(blobs.len())
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, Vec<u8>)>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = join(prefix, &entry.file_name().to_string_lossy());
+
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), &name, files)?;
+        } else {
+            files.push((name, std::fs::read(entry.path())?));
+        }
+    }
+
+    Ok(())
+}
+
+/// 与 `base` 同目录下的 `name`，用于解析模型等文件中的相对路径，结果中的 `.` 和 `..` 已消去
+pub fn sibling(base: &str, name: &str) -> String {
+    let (scheme, path) = match base.split_once("://") {
+        Some((scheme, path)) => (format!("{scheme}://"), path),
+        None => (String::new(), base),
+    };
+
+    let dir = path.rfind(['/', '\\']).map_or("", |i| &path[..i]);
+    let path = join(dir, name);
+
+    if scheme.is_empty() {
+        // 磁盘路径保留开头的 `/`，开头无法消去的 `..` 仍然有效
+        let root = if path.starts_with(['/', '\\']) { "/" } else { "" };
+        format!("{root}{}", clean(&path))
+    } else {
+        // 超出挂载根目录时保留原样，读取时由 split_uri 报错
+        format!("{scheme}{}", normalize(&path).unwrap_or(path))
+    }
+}
+
+/// 拆分 `name://path` 并规范化路径，不带前缀时返回 None
+fn split_uri(uri: &str) -> Option<io::Result<(&str, String)>> {
+    let (name, path) = uri.split_once("://")?;
+    Some(normalize(path).map(|path| (name, path)))
+}
+
+/// 挂载点内的相对路径：消去 `.` 和 `..`，分隔符统一为 `/`，超出挂载根目录时返回错误
+fn normalize(path: &str) -> io::Result<String> {
+    let cleaned = clean(path);
+    if cleaned == ".." || cleaned.starts_with("../") {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("路径超出挂载根目录: {path}")));
+    }
+    Ok(cleaned)
+}
+
+/// 按 `/` 或 `\` 拆分并消去 `.` 和 `..`，无法消去的 `..` 保留在开头
+fn clean(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+
+    for part in path.split(['/', '\\']) {
+        match part {
+            "" | "." => (),
+            ".." if parts.last().is_some_and(|last| *last != "..") => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+fn join(dir: &str, name: &str) -> String {
+    let dir = dir.trim_end_matches('/');
+    if dir.is_empty() { name.to_string() } else { format!("{dir}/{name}") }
+}
+
+fn invalid_pack(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每个测试使用独立的临时目录，避免并行测试互相干扰
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("azer-vfs-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_sample_tree(root: &Path) {
+        std::fs::create_dir_all(root.join("images")).unwrap();
+        std::fs::write(root.join("readme.txt"), b"azer").unwrap();
+        // 重复内容可以被压缩
+        std::fs::write(root.join("images/tile.bin"), vec![7u8; 4096]).unwrap();
+        std::fs::write(root.join("images/empty.bin"), b"").unwrap();
+    }
+
+    fn round_trip(name: &str, compress: bool) {
+        let dir = temp_dir(name);
+        let src = dir.join("src");
+        write_sample_tree(&src);
+
+        let pack_path = dir.join(ASSETS_PACK_NAME);
+        assert_eq!(write_pack(&src, &pack_path, compress).unwrap(), 3);
+
+        let pack = PackSource::open(&pack_path).unwrap();
+        assert_eq!(pack.read("readme.txt").unwrap(), b"azer");
+        assert_eq!(pack.read("images/tile.bin").unwrap(), vec![7u8; 4096]);
+        assert!(pack.read("images/empty.bin").unwrap().is_empty());
+        assert_eq!(pack.read("missing.txt").unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(pack.entries["images/tile.bin"].compressed, compress);
+
+        let mut images = pack.list("images").unwrap();
+        images.sort();
+        assert_eq!(images, ["images/empty.bin", "images/tile.bin"]);
+        assert_eq!(pack.list("").unwrap(), ["readme.txt"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn pack_round_trip_uncompressed() {
+        round_trip("uncompressed", false);
+    }
+
+    #[test]
+    fn pack_round_trip_compressed() {
+        round_trip("compressed", true);
+    }
+
+    #[test]
+    fn pack_rejects_truncated_archive() {
+        let dir = temp_dir("truncated");
+        let src = dir.join("src");
+        write_sample_tree(&src);
+
+        let pack_path = dir.join(ASSETS_PACK_NAME);
+        write_pack(&src, &pack_path, false).unwrap();
+
+        // 截掉数据区末尾，最后一个条目超出文件长度
+        let bytes = std::fs::read(&pack_path).unwrap();
+        std::fs::write(&pack_path, &bytes[..bytes.len() - 1]).unwrap();
+        let err = PackSource::open(&pack_path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // 条目数远大于文件能容纳的数量
+        let mut bytes = bytes.clone();
+        bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&pack_path, &bytes).unwrap();
+        let err = PackSource::open(&pack_path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // 第一个条目的路径长度超出文件长度
+        let mut bytes = std::fs::read(&pack_path).unwrap();
+        bytes[8..12].copy_from_slice(&1u32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&pack_path, &bytes).unwrap();
+        let err = PackSource::open(&pack_path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn split_uri_with_and_without_prefix() {
+        assert_eq!(split_uri("assets://images/a.png").unwrap().unwrap(), ("assets", "images/a.png".to_string()));
+        assert_eq!(split_uri("assets:///images/a.png").unwrap().unwrap(), ("assets", "images/a.png".to_string()));
+        assert!(split_uri("src/assets/images/a.png").is_none());
+    }
+
+    #[test]
+    fn split_uri_normalizes_path() {
+        assert_eq!(split_uri("assets://models/../textures/a.png").unwrap().unwrap().1, "textures/a.png");
+        assert_eq!(split_uri("assets://./models\\box.obj").unwrap().unwrap().1, "models/box.obj");
+        assert_eq!(split_uri("assets://models//./a/..").unwrap().unwrap().1, "models");
+
+        let err = split_uri("assets://models/../../secret.txt").unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(split_uri("assets://..").unwrap().is_err());
+    }
+
+    #[test]
+    fn sibling_with_and_without_prefix() {
+        assert_eq!(sibling("assets://models/box.gltf", "box.bin"), "assets://models/box.bin");
+        assert_eq!(sibling("assets://box.gltf", "box.bin"), "assets://box.bin");
+        assert_eq!(sibling("models/box.gltf", "box.bin"), "models/box.bin");
+        assert_eq!(sibling("models\\box.gltf", "box.bin"), "models/box.bin");
+        assert_eq!(sibling("box.gltf", "box.bin"), "box.bin");
+    }
+
+    #[test]
+    fn sibling_normalizes_relative_name() {
+        assert_eq!(sibling("assets://models/box.obj", "../textures/a.png"), "assets://textures/a.png");
+        assert_eq!(sibling("assets://models/box.obj", "./box.mtl"), "assets://models/box.mtl");
+        assert_eq!(sibling("assets://models/box.obj", "..\\textures\\a.png"), "assets://textures/a.png");
+        // 超出挂载根目录时原样保留，交给读取时报错
+        assert_eq!(sibling("assets://box.obj", "../a.png"), "assets://../a.png");
+
+        assert_eq!(sibling("/home/azer/models/box.obj", "../a.png"), "/home/azer/a.png");
+        assert_eq!(sibling("models/box.obj", "../../a.png"), "../a.png");
+    }
+
+    #[test]
+    fn parent_reference_resolves_in_directory_and_pack() {
+        let dir = temp_dir("parent");
+        let src = dir.join("src");
+        write_sample_tree(&src);
+
+        let pack_path = dir.join(ASSETS_PACK_NAME);
+        write_pack(&src, &pack_path, true).unwrap();
+
+        let mut vfs = Vfs::new();
+        vfs.mount("dir", Box::new(DirectorySource::new(src.clone())));
+        vfs.mount("pak", Box::new(PackSource::open(&pack_path).unwrap()));
+
+        for name in ["dir", "pak"] {
+            assert_eq!(vfs.read(&format!("{name}://images/../readme.txt")).unwrap(), b"azer");
+            assert_eq!(vfs.read(&format!("{name}://./images\\tile.bin")).unwrap(), vec![7u8; 4096]);
+
+            // 模型中引用上级目录的文件
+            let uri = sibling(&format!("{name}://images/tile.bin"), "../readme.txt");
+            assert_eq!(vfs.read(&uri).unwrap(), b"azer");
+
+            let mut images = vfs.list(&format!("{name}://readme/../images/.")).unwrap();
+            images.sort();
+            assert_eq!(images, [format!("{name}://images/empty.bin"), format!("{name}://images/tile.bin")]);
+
+            let err = vfs.read(&format!("{name}://images/../../readme.txt")).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+
+        assert_eq!(vfs.resolve("dir://images/../readme.txt"), Some(src.join("readme.txt")));
+        assert_eq!(vfs.resolve("dir://../readme.txt"), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
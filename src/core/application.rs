@@ -1,16 +1,18 @@
-use crate::api::vulkan::{RenderDirty, Vulkan};
+use crate::api::vulkan::{RenderDirty, Vulkan, VulkanConfig};
+use crate::asset::vfs;
 use crate::core::core::print_mem;
 use crate::core::delta_time::DeltaTime;
-use crate::core::event::Event;
+use crate::core::event::{Event, ResizeEvent};
 use crate::core::input::InputState;
 use crate::core::layer::Layer;
 use crate::core::layer_stack::LayerStack;
+use crate::renderer::camera::scaling::ScalingPolicy;
 use crate::renderer::image_buffer_man::ImageBufferManager;
 use crate::renderer::renderer::Renderer;
 use crate::ui;
 use crate::ui::imgui_renderer::ImGuiRenderer;
 use imgui::{Condition, FontConfig, FontGlyphRanges, FontSource};
-use log::info;
+use log::{info, warn};
 use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::Instant;
@@ -21,6 +23,7 @@ use winit::window::{Window, WindowId};
 
 const FIXED_PHYSICS_STEP: f64 = 1.0/60.0; // 固定物理步长
 const MAX_PHYSICS_STEPS: usize = 10; // 最大物理步次
+const UI_FONT_PATH: &str = "assets://fonts/MicrosoftYaHei.ttf";
 
 enum AppState {
     Uninitialized {
@@ -52,6 +55,7 @@ impl Default for AppState {
 
 pub struct Application {
     state: AppState,
+    scaling_policy: ScalingPolicy,
 }
 
 impl ApplicationHandler for Application {
@@ -76,7 +80,10 @@ impl ApplicationHandler for Application {
             print_mem("window initialed");
 
             // 创建 vulkan
-            let vulkan = Vulkan::new(window.clone());
+            let mut vulkan = Vulkan::new(window.clone(), VulkanConfig {
+                scaling_policy: self.scaling_policy,
+                ..VulkanConfig::default()
+            });
 
             print_mem("vulkan initialed");
 
@@ -84,9 +91,11 @@ impl ApplicationHandler for Application {
 
             print_mem("map initialed");
 
+            vfs::mount_default_assets();
+
             let (tx, rx) = mpsc::channel();
             let handle = thread::spawn(move || {
-                let data = include_bytes!("E:\\Projects\\Azer\\Azer\\src\\assets\\fonts\\MicrosoftYaHei.ttf");
+                let data = vfs::read(UI_FONT_PATH);
                 let _ = tx.send(data);
             });
 
@@ -94,34 +103,55 @@ impl ApplicationHandler for Application {
             let mut renderer = Renderer::new(
                 vulkan.device.clone(),
                 vulkan.queue.clone(),
-                window.clone(),
+                window.inner_size().into(),
                 vulkan.render_pass.clone(),
+                vulkan.frames_in_flight(),
                 &mut map
             );
             print_mem("renderer initialed");
             
             let _ = handle.join();
-            let data = rx.recv().unwrap();
             let font_size = 24.0;
             let mut imgui = imgui::Context::create();
-            imgui.fonts().add_font(&[FontSource::TtfData {
-                data,
-                size_pixels: 30.0,
-                config: Some(FontConfig {
-                    size_pixels: font_size,
-                    glyph_ranges: FontGlyphRanges::chinese_full(),
-                    ..FontConfig::default()
-                })
-            }]);
+            match rx.recv().unwrap() {
+                Ok(data) => {
+                    imgui.fonts().add_font(&[FontSource::TtfData {
+                        data: &data,
+                        size_pixels: 30.0,
+                        config: Some(FontConfig {
+                            size_pixels: font_size,
+                            glyph_ranges: FontGlyphRanges::chinese_full(),
+                            ..FontConfig::default()
+                        })
+                    }]);
+                }
+                Err(e) => {
+                    // 缺少字体时退回 ImGui 内置字体，中文无法显示
+                    warn!("加载字体失败 {}: {}，使用默认字体", UI_FONT_PATH, e);
+                    imgui.fonts().add_font(&[FontSource::DefaultFontData {
+                        config: Some(FontConfig {
+                            size_pixels: font_size,
+                            ..FontConfig::default()
+                        })
+                    }]);
+                }
+            }
             print_mem("imgui initialed");
 
             // 层栈初始化
             layer_stack.iter_mut().for_each(|layer| layer.on_ready(&mut renderer));
 
+            // 渲染器按整个窗口创建，首帧前按缩放策略重建视口
+            vulkan.dirty.insert(RenderDirty::VIEWPORT);
+            broadcast_resize(&mut layer_stack, &vulkan, &window);
+
             print_mem("layer initialed");
 
             // 初始化输入状态
-            let input_state = InputState::default();
+            let mut input_state = InputState::default();
+            let size = window.inner_size();
+            input_state.update_window_size(size.width, size.height);
+            input_state.update_scale_factor(window.scale_factor());
 
             let imgui_renderer = ImGuiRenderer::new(
                 window.clone(),
@@ -129,6 +159,7 @@ impl ApplicationHandler for Application {
                 vulkan.render_pass.clone(),
                 renderer.allocators.buffer_allocator.clone(),
                 renderer.allocators.descriptor_set_allocator.clone(),
+                vulkan.frames_in_flight(),
                 &mut imgui,
                 &mut map
             );
@@ -172,6 +203,9 @@ impl ApplicationHandler for Application {
             WindowEvent::CloseRequested => {
                 info!("检测到点击关闭按钮，开始清理，请不要退出应用！");
 
+                // 等待 GPU 完成所有在途帧
+                vulkan.wait_for_frames();
+
                 // 清理层栈
                 layer_stack.iter_mut().for_each(|layer| layer.on_close());
                 layer_stack.clear();
@@ -181,7 +215,7 @@ impl ApplicationHandler for Application {
                 return;
             },
             WindowEvent::RedrawRequested => {
-                if vulkan.dirty.contains(RenderDirty::SWAPCHAIN) {
+                if vulkan.dirty.intersects(RenderDirty::SWAPCHAIN | RenderDirty::VIEWPORT) {
                     vulkan.recreate_swapchain(window.clone(), renderer);
                     vulkan.dirty.remove(RenderDirty::SWAPCHAIN);
                 }
@@ -202,11 +236,17 @@ impl ApplicationHandler for Application {
 
                 let ui = imgui.frame();
 
+                let stats = renderer.stats();
+                let shader_error = renderer.shader_error().map(str::to_string);
 
                 ui.window("Azer Core")
                     .size([300.0, 100.0], Condition::FirstUseEver)
                     .build(|| {
                         ui.color_edit4("Clear Color", clear_color);
+                        ui.text(format!("Draw Calls: {}  Instances: {}", stats.draw_calls, stats.instances));
+                        if let Some(error) = &shader_error {
+                            ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
+                        }
                     });
 
                 layer_stack.iter_mut().for_each(|layer| {
@@ -217,10 +257,16 @@ impl ApplicationHandler for Application {
 
                 vulkan.submit(renderer, layer_stack, *clear_color, map, imgui_renderer, draw_data);
             },
-            WindowEvent::Resized(_size) => {
+            WindowEvent::Resized(size) => {
+                input_state.update_window_size(size.width, size.height);
                 vulkan.dirty.insert(RenderDirty::SWAPCHAIN);
-                vulkan.dirty.insert(RenderDirty::PIPELINE);
+                vulkan.dirty.insert(RenderDirty::VIEWPORT);
                 vulkan.dirty.insert(RenderDirty::COMMAND_BUF);
+                broadcast_resize(layer_stack, vulkan, window);
+            },
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                input_state.update_scale_factor(*scale_factor);
+                broadcast_resize(layer_stack, vulkan, window);
             },
             _ => ()
         }
@@ -314,7 +360,20 @@ impl Application {
         Self {
             state: AppState::Uninitialized {
                 layer_stack: LayerStack::new(),
-            }
+            },
+            scaling_policy: ScalingPolicy::default(),
+        }
+    }
+
+    /// 设置窗口尺寸变化时的缩放策略，运行中修改会立即重建视口并通知各层
+    pub fn set_scaling_policy(&mut self, policy: ScalingPolicy) {
+        self.scaling_policy = policy;
+
+        if let AppState::Running { window, layer_stack, vulkan, .. } = &mut self.state {
+            vulkan.scaling_policy = policy;
+            vulkan.dirty.insert(RenderDirty::VIEWPORT);
+            broadcast_resize(layer_stack, vulkan, window);
+            window.request_redraw();
         }
     }
     pub fn push_layer(&mut self, layer: Box<dyn Layer>) {
@@ -329,6 +388,23 @@ impl Application {
     }
 }
 
+/// 按当前窗口尺寸和缩放策略通知所有图层，不可被拦截
+pub fn broadcast_resize(layer_stack: &mut LayerStack, vulkan: &Vulkan, window: &Window) {
+    let size = window.inner_size();
+
+    if size.width == 0 || size.height == 0 {
+        return;
+    }
+
+    let event = ResizeEvent {
+        width: size.width,
+        height: size.height,
+        scaling: vulkan.scaling(size.into(), window.scale_factor() as f32),
+    };
+
+    layer_stack.iter_mut().for_each(|layer| layer.on_resize(&event));
+}
+
 const PHYSICS_DELTA: DeltaTime = DeltaTime::new_const(FIXED_PHYSICS_STEP);
 pub fn physics_update(layer_stack: &mut LayerStack, duration: f64, accumulated_time: &mut f64) {
     let mut step: usize = 0;
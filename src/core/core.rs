@@ -34,4 +34,7 @@ pub fn print_mem(tag: &str) {
             pmc.WorkingSetSize / 1024 / 1024
         );
     }
-}
\ No newline at end of file
+}
+
+#[cfg(not(windows))]
+pub fn print_mem(_tag: &str) {}
@@ -1,7 +1,18 @@
+use crate::renderer::camera::scaling::Scaling;
 use winit::event::WindowEvent;
 
 #[derive(Debug, Clone)]
 pub struct Event<'a> {
     pub event: &'a WindowEvent,
     pub handled: bool,
+}
+
+/// 窗口尺寸变化，会广播给所有图层
+#[derive(Debug, Copy, Clone)]
+pub struct ResizeEvent {
+    /// 窗口尺寸，物理像素
+    pub width: u32,
+    pub height: u32,
+    /// 按当前缩放策略计算出的视口和相机参数
+    pub scaling: Scaling,
 }
\ No newline at end of file
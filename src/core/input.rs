@@ -2,11 +2,27 @@ use std::collections::HashSet;
 use winit::event::MouseButton;
 use winit::keyboard::KeyCode;
 
-#[derive(Default)]
 pub struct InputState {
     pressed_keys: HashSet<KeyCode>,
     mouse_buttons: HashSet<MouseButton>,
+    /// 物理像素坐标
     mouse_position: (f64, f64),
+    /// 窗口内部区域的物理像素尺寸
+    window_size: (u32, u32),
+    /// DPI 缩放，物理像素 = 逻辑像素 * scale_factor
+    scale_factor: f64,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            pressed_keys: HashSet::new(),
+            mouse_buttons: HashSet::new(),
+            mouse_position: (0.0, 0.0),
+            window_size: (0, 0),
+            scale_factor: 1.0,
+        }
+    }
 }
 
 impl InputState {
@@ -18,10 +34,24 @@ impl InputState {
         self.mouse_buttons.contains(&button)
     }
 
+    /// 鼠标位置，物理像素，原点在窗口左上角
     pub fn mouse_pos(&self) -> (f64, f64) {
         self.mouse_position
     }
 
+    /// 鼠标位置，逻辑像素（与 ImGui 坐标一致）
+    pub fn mouse_pos_logical(&self) -> (f64, f64) {
+        (self.mouse_position.0 / self.scale_factor, self.mouse_position.1 / self.scale_factor)
+    }
+
+    pub fn window_size(&self) -> (u32, u32) {
+        self.window_size
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
     // 更新按键状态
     pub fn update_key(&mut self, key: winit::keyboard::PhysicalKey, pressed: bool) {
         if let winit::keyboard::PhysicalKey::Code(key_code) = key {
@@ -45,4 +75,12 @@ impl InputState {
     pub fn update_mouse_pos(&mut self, x: f64, y: f64) {
         self.mouse_position = (x, y);
     }
+
+    pub fn update_window_size(&mut self, width: u32, height: u32) {
+        self.window_size = (width, height);
+    }
+
+    pub fn update_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 pub use crate::core::delta_time::DeltaTime;
-use crate::core::event::Event;
+use crate::core::event::{Event, ResizeEvent};
 use crate::core::input::InputState;
 use crate::renderer::image_buffer_man::ImageBufferManager;
 use crate::renderer::renderer::Renderer;
@@ -14,4 +14,7 @@ pub trait Layer: Send + Sync {
     fn on_physics_update(&mut self, delta: &DeltaTime);
     fn on_event(&mut self, event: &Event);
     fn on_close(&mut self);
+
+    /// 窗口尺寸变化，启动时也会调用一次
+    fn on_resize(&mut self, _event: &ResizeEvent) {}
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 pub mod core;
 pub mod api;
 pub mod renderer;
+pub mod asset;
 
-pub mod ui;
\ No newline at end of file
+pub mod ui;
+pub mod testing;
\ No newline at end of file
@@ -1,7 +1,10 @@
-mod camera_controller_layer;
 mod render_layer;
+mod screenshot_layer;
 
 use azer::core::{application::Application, logger};
+use azer::renderer::camera::camera2d::Camera2D;
+use azer::renderer::camera::camera2d_controller::Camera2DController;
+use glam::Vec2;
 use log::info;
 use winit::event_loop::{ControlFlow, EventLoop};
 
@@ -17,8 +20,10 @@ async fn main() {
     info!("窗口模块初始化成功！");
 
     let mut app: Application = Application::new();
-    app.push_layer(Box::new(camera_controller_layer::NewLayer::new()));
+    let camera = Camera2D::new(16.0 / 9.0, 1.0, -1.0, 1.0, Vec2::ZERO);
+    app.push_layer(Box::new(Camera2DController::new(camera)));
     app.push_layer(Box::new(render_layer::RenderLayer::new()));
+    app.push_layer(Box::new(screenshot_layer::ScreenshotLayer::new()));
 
     event_loop.run_app(&mut app).unwrap();
 }
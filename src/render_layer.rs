@@ -1,3 +1,5 @@
+use azer::asset::handle::Handle;
+use azer::asset::texture::Texture;
 use azer::core::delta_time::DeltaTime;
 use azer::core::event::Event;
 use azer::core::input::InputState;
@@ -9,9 +11,12 @@ use glam::{Quat, Vec3};
 use imgui::Ui;
 use log::info;
 
+const IMAGE_PATH: &str = "assets://images/sample.png";
+
 pub struct RenderLayer {
     pub rotation: Quat,
     pub angle: f32,
+    pub image: Option<Handle<Texture>>,
 }
 
 impl RenderLayer {
@@ -19,12 +24,14 @@ impl RenderLayer {
         Self {
             rotation: Quat::IDENTITY,
             angle: 0.0,
+            image: None,
         }
     }
 }
 
 impl Layer for RenderLayer {
-    fn on_ready(&mut self, _renderer: &mut Renderer) {
+    fn on_ready(&mut self, renderer: &mut Renderer) {
+        self.image = Some(renderer.load_texture(IMAGE_PATH));
         info!("RenderLayer ready!")
     }
 
@@ -33,7 +40,7 @@ impl Layer for RenderLayer {
         self.rotation = Quat::from_rotation_z(self.angle);
     }
 
-    fn on_render(&mut self, renderer: &mut Renderer, map: &mut ImageBufferManager) {
+    fn on_render(&mut self, renderer: &mut Renderer, _map: &mut ImageBufferManager) {
         for i in 0..5 {
             for j in 0..5 {
                 let mut transform = Transform::default();
@@ -47,9 +54,14 @@ impl Layer for RenderLayer {
             }
         }
 
+        // 图片始终显示在棋盘格上方，与提交顺序无关
+        renderer.set_z_index(1);
         let mut transform = Transform::default();
         transform.scale = Vec3::new(0.1,0.1, 1.0);
-        renderer.draw_image(transform, "E:\\360MoveData\\Users\\w1926\\OneDrive\\图片\\Camera Roll\\Snipaste_2025-08-19_01-57-10.png", map);
+        if let Some(image) = &self.image {
+            renderer.draw_texture(transform, image);
+        }
+        renderer.set_z_index(0);
     }
 
     fn on_imgui_render(&mut self, _ui: &mut Ui) {
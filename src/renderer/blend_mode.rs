@@ -0,0 +1,75 @@
+use vulkano::pipeline::graphics::color_blend::{AttachmentBlend, BlendFactor, BlendOp, ColorBlendAttachmentState};
+
+/// 2D 绘制的混合模式，每种模式对应一条缓存的图形管线
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// 不混合，直接覆盖
+    Opaque,
+    /// 常规 alpha 混合（非预乘）
+    #[default]
+    Alpha,
+    /// 叠加：dst + src * src.a
+    Additive,
+    /// 正片叠底：dst * src
+    Multiply,
+    /// 预乘 alpha：颜色需已乘以 alpha
+    Premultiplied,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 5] = [
+        BlendMode::Opaque,
+        BlendMode::Alpha,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+        BlendMode::Premultiplied,
+    ];
+
+    /// 是否需要与已绘制内容混合
+    pub fn is_transparent(&self) -> bool {
+        *self != BlendMode::Opaque
+    }
+
+    pub fn attachment_state(&self) -> ColorBlendAttachmentState {
+        let blend = match self {
+            BlendMode::Opaque => None,
+            BlendMode::Alpha => Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::SrcAlpha,
+                dst_color_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::One,
+                dst_alpha_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                alpha_blend_op: BlendOp::Add,
+            }),
+            BlendMode::Additive => Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::SrcAlpha,
+                dst_color_blend_factor: BlendFactor::One,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::Zero,
+                dst_alpha_blend_factor: BlendFactor::One,
+                alpha_blend_op: BlendOp::Add,
+            }),
+            BlendMode::Multiply => Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::DstColor,
+                dst_color_blend_factor: BlendFactor::Zero,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::Zero,
+                dst_alpha_blend_factor: BlendFactor::One,
+                alpha_blend_op: BlendOp::Add,
+            }),
+            BlendMode::Premultiplied => Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::One,
+                dst_color_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::One,
+                dst_alpha_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                alpha_blend_op: BlendOp::Add,
+            }),
+        };
+
+        ColorBlendAttachmentState {
+            blend,
+            ..ColorBlendAttachmentState::default()
+        }
+    }
+}
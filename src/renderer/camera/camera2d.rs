@@ -1,5 +1,7 @@
-use crate::renderer::camera::Camera;
+use crate::renderer::camera::scaling::Scaling;
+use crate::renderer::camera::{Camera, ScreenViewport};
 use glam::{Mat4, Vec2, Vec3};
+use log::warn;
 
 #[derive(Clone)]
 pub struct Camera2D {
@@ -8,6 +10,8 @@ pub struct Camera2D {
     near: f32,
     far: f32,
     pub position: Vec2,
+    /// 缩放策略给出的可见高度倍数
+    view_scale: f32,
 
     view_matrix: Mat4,
     projection_matrix: Mat4,
@@ -15,7 +19,12 @@ pub struct Camera2D {
 }
 
 impl Camera2D {
+    /// `near` / `far` 需覆盖 z ∈ [-1, 1]（near <= -1、far >= 1），
+    /// 渲染器把 z_index 映射到这个范围，常用 `near = -1, far = 1`
     pub fn new(aspect_ratio: f32, zoom: f32, near: f32, far: f32, position: Vec2) -> Self {
+        if near > -1.0 || far < 1.0 {
+            warn!("Camera2D 的深度范围 [{}, {}] 未覆盖 [-1, 1]，部分 z_index 的精灵会被裁掉", near, far);
+        }
 
         let projection_matrix = Self::get_projection_matrix(aspect_ratio, zoom, near, far);
         let view_matrix = Self::get_view_matrix(position);
@@ -26,6 +35,7 @@ impl Camera2D {
             near,
             far,
             position,
+            view_scale: 1.0,
 
             view_matrix,
             projection_matrix,
@@ -37,6 +47,32 @@ impl Camera2D {
         self.aspect_ratio = aspect_ratio;
     }
 
+    pub fn aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    /// 窗口尺寸变化后应用缩放策略的结果
+    pub fn apply_scaling(&mut self, scaling: &Scaling) {
+        self.aspect_ratio = scaling.aspect_ratio;
+        self.view_scale = scaling.view_scale;
+    }
+
+    /// 可见区域的半宽和半高（世界单位）
+    pub fn half_extent(&self) -> Vec2 {
+        Vec2::new(self.aspect_ratio, 1.0) * self.zoom * self.view_scale
+    }
+
+    /// 屏幕物理像素坐标对应的 2D 世界坐标
+    pub fn screen_to_world_2d(&self, screen: Vec2, viewport: &ScreenViewport) -> Vec2 {
+        self.screen_to_world(screen, viewport).truncate()
+    }
+
+    /// 2D 世界坐标对应的屏幕物理像素坐标
+    pub fn world_to_screen_2d(&self, world: Vec2, viewport: &ScreenViewport) -> Vec2 {
+        // 正交投影的 w 恒为 1，不会返回 None
+        self.world_to_screen(world.extend(0.0), viewport).unwrap_or_default()
+    }
+
     fn get_projection_matrix(aspect_ratio: f32, zoom: f32, near: f32, far: f32) -> Mat4 {
         let mut m = Mat4::orthographic_rh(-aspect_ratio * zoom, aspect_ratio * zoom, -zoom, zoom, near, far);
         m.y_axis.y *= -1.0;
@@ -62,8 +98,68 @@ impl Camera for Camera2D {
     }
 
     fn update(&mut self) {
-        self.projection_matrix = Self::get_projection_matrix(self.aspect_ratio, self.zoom, self.near, self.far);
+        self.projection_matrix = Self::get_projection_matrix(self.aspect_ratio, self.zoom * self.view_scale, self.near, self.far);
         self.view_matrix = Self::get_view_matrix(self.position);
         self.view_projection_matrix = self.projection_matrix * self.view_matrix;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::camera::scaling::ScalingPolicy;
+
+    fn viewport() -> ScreenViewport {
+        ScreenViewport {
+            offset: Vec2::new(100.0, 50.0),
+            size: Vec2::new(800.0, 450.0),
+            scale_factor: 1.0,
+        }
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!(a.abs_diff_eq(b, 1e-3), "{a} != {b}");
+    }
+
+    #[test]
+    fn screen_world_round_trip_with_offset_viewport() {
+        let mut camera = Camera2D::new(16.0 / 9.0, 2.0, -1.0, 1.0, Vec2::new(3.0, -2.0));
+        camera.update();
+        let viewport = viewport();
+
+        for screen in [Vec2::new(100.0, 50.0), Vec2::new(500.0, 275.0), Vec2::new(837.5, 412.25)] {
+            let world = camera.screen_to_world_2d(screen, &viewport);
+            assert_near(camera.world_to_screen_2d(world, &viewport), screen);
+        }
+    }
+
+    #[test]
+    fn viewport_top_centre_is_up_in_world() {
+        let mut camera = Camera2D::new(16.0 / 9.0, 2.0, -1.0, 1.0, Vec2::new(3.0, -2.0));
+        camera.update();
+        let viewport = viewport();
+
+        let centre = viewport.offset + viewport.size * 0.5;
+        assert_near(camera.screen_to_world_2d(centre, &viewport), camera.position);
+
+        // 屏幕 y 向下，世界 y 向上
+        let top = Vec2::new(centre.x, viewport.offset.y);
+        let expected = camera.position + Vec2::new(0.0, camera.half_extent().y);
+        assert_near(camera.screen_to_world_2d(top, &viewport), expected);
+    }
+
+    #[test]
+    fn fill_view_scale_shrinks_visible_area() {
+        let mut camera = Camera2D::new(16.0 / 9.0, 2.0, -1.0, 1.0, Vec2::ZERO);
+        let scaling = ScalingPolicy::Fill { width: 800, height: 600 }.apply([1600, 600], 1.0);
+        camera.apply_scaling(&scaling);
+        camera.update();
+
+        assert_eq!(camera.half_extent().y, 1.0);
+
+        let viewport = scaling.viewport;
+        let corner = camera.screen_to_world_2d(viewport.offset, &viewport);
+        assert_near(corner, Vec2::new(-camera.half_extent().x, camera.half_extent().y));
+        assert_near(camera.world_to_screen_2d(corner, &viewport), viewport.offset);
+    }
+}
@@ -0,0 +1,440 @@
+use crate::core::delta_time::DeltaTime;
+use crate::core::event::{Event, ResizeEvent};
+use crate::core::input::InputState;
+use crate::core::layer::Layer;
+use crate::renderer::camera::camera2d::Camera2D;
+use crate::renderer::camera::{Camera, ScreenViewport};
+use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::renderer::Renderer;
+use glam::Vec2;
+use imgui::{Condition, Ui};
+use winit::event::{MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::KeyCode;
+
+/// 2D 相机控制器的按键绑定
+#[derive(Copy, Clone, Debug)]
+pub struct Camera2DBindings {
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    /// 按住拖动画面的鼠标键
+    pub drag: MouseButton,
+}
+
+impl Default for Camera2DBindings {
+    fn default() -> Self {
+        Self {
+            up: KeyCode::KeyW,
+            down: KeyCode::KeyS,
+            left: KeyCode::KeyA,
+            right: KeyCode::KeyD,
+            drag: MouseButton::Left,
+        }
+    }
+}
+
+/// 2D 相机控制器图层：按键平移、拖动、以光标为中心平滑缩放、世界边界限制、跟随目标和屏幕震动
+pub struct Camera2DController {
+    pub camera: Camera2D,
+    pub bindings: Camera2DBindings,
+
+    /// 按键平移速度，单位为每秒多少个视口高度
+    pub move_speed: f32,
+    /// 每格滚轮缩放的比例
+    pub zoom_step: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    /// 缩放平滑系数，越大越快到达目标缩放
+    pub zoom_smoothing: f32,
+
+    /// 相机可见范围限制在 (min, max) 内，可见范围大于边界时居中
+    pub bounds: Option<(Vec2, Vec2)>,
+    /// 跟随目标，设置后按键平移和拖动不生效
+    pub follow: Option<Vec2>,
+    /// 跟随平滑系数，越大越紧
+    pub follow_damping: f32,
+    /// 只为该分屏视图提供相机，None 表示所有视图；此时拖动和缩放按光标所在的视图换算
+    pub view: Option<usize>,
+
+    /// 不含震动偏移的相机位置
+    position: Vec2,
+    target_zoom: f32,
+    /// 缩放中心（屏幕像素坐标）
+    zoom_anchor: Option<(f64, f64)>,
+    /// 整个场景视口，视图尚未渲染过时使用
+    viewport: ScreenViewport,
+    /// 各分屏视图的屏幕区域，在 on_render 中记录
+    views: Vec<ScreenViewport>,
+    mouse_pos: (f64, f64),
+
+    shake_intensity: f32,
+    shake_duration: f32,
+    shake_time: f32,
+    rng: u32,
+}
+
+impl Camera2DController {
+    pub fn new(camera: Camera2D) -> Self {
+        Self {
+            position: camera.position,
+            target_zoom: camera.zoom,
+            camera,
+            bindings: Camera2DBindings::default(),
+            move_speed: 1.0,
+            zoom_step: 0.1,
+            min_zoom: 0.05,
+            max_zoom: 100.0,
+            zoom_smoothing: 12.0,
+            bounds: None,
+            follow: None,
+            follow_damping: 5.0,
+            view: None,
+            zoom_anchor: None,
+            viewport: ScreenViewport::new(Vec2::new(1280.0, 720.0), 1.0),
+            views: Vec::new(),
+            mouse_pos: (0.0, 0.0),
+            shake_intensity: 0.0,
+            shake_duration: 0.0,
+            shake_time: 0.0,
+            rng: 0x9E37_79B9,
+        }
+    }
+
+    pub fn with_bindings(mut self, bindings: Camera2DBindings) -> Self {
+        self.bindings = bindings;
+        self
+    }
+
+    pub fn with_bounds(mut self, min: Vec2, max: Vec2) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// 相机位置（不含震动偏移）
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// 直接移动相机，不经过平滑
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+
+    /// 设置目标缩放，之后平滑过渡；值会被限制在 [min_zoom, max_zoom]
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.target_zoom = zoom.clamp(self.min_zoom, self.max_zoom);
+        self.zoom_anchor = None;
+    }
+
+    /// 震动屏幕，`intensity` 为最大偏移（世界单位），随时间线性衰减
+    pub fn shake(&mut self, intensity: f32, duration: f32) {
+        self.shake_intensity = self.shake_intensity.max(intensity);
+        self.shake_duration = duration.max(0.0);
+        self.shake_time = duration.max(0.0);
+    }
+
+    /// 按滚轮格数缩放，以当前光标为中心
+    fn zoom_by(&mut self, lines: f32) {
+        // 按比例缩放，zoom 始终为正
+        self.target_zoom = (self.target_zoom * (1.0 - self.zoom_step).powf(lines))
+            .clamp(self.min_zoom, self.max_zoom);
+        self.zoom_anchor = Some(self.mouse_pos);
+    }
+
+    /// 屏幕坐标所在视图的区域：只控制一个视图时总是该视图，找不到时为整个场景视口
+    fn viewport_at(&self, screen: (f64, f64)) -> ScreenViewport {
+        let screen = Vec2::new(screen.0 as f32, screen.1 as f32);
+
+        let viewport = match self.view {
+            Some(view) => self.views.get(view),
+            None => self.views.iter().find(|viewport| viewport.contains(screen)),
+        };
+
+        viewport.copied().unwrap_or(self.viewport)
+    }
+
+    /// 屏幕像素坐标在指定缩放下相对相机中心的世界偏移，宽高比取自 `viewport`
+    fn screen_offset(&self, screen: (f64, f64), viewport: &ScreenViewport, zoom: f32) -> Vec2 {
+        let ndc = viewport.screen_to_ndc(Vec2::new(screen.0 as f32, screen.1 as f32));
+
+        // 分屏时各视图宽高比不同，相机的宽高比只对应最后渲染的视图
+        let half_height = self.camera.half_extent().y * (zoom / self.camera.zoom);
+        let half = Vec2::new(half_height * viewport.size.x / viewport.size.y.max(1.0), half_height);
+
+        // NDC y 向下，世界 y 向上；可见范围与缩放成正比
+        Vec2::new(ndc.x, -ndc.y) * half
+    }
+
+    fn update_zoom(&mut self, dt: f32) {
+        let old_zoom = self.camera.zoom;
+        let t = 1.0 - (-self.zoom_smoothing * dt).exp();
+        let mut new_zoom = old_zoom + (self.target_zoom - old_zoom) * t;
+
+        if (new_zoom - self.target_zoom).abs() < self.target_zoom * 1e-4 {
+            new_zoom = self.target_zoom;
+        }
+
+        // 保持光标下的世界坐标不动
+        if let Some(anchor) = self.zoom_anchor {
+            let viewport = self.viewport_at(anchor);
+            self.position += self.screen_offset(anchor, &viewport, old_zoom) - self.screen_offset(anchor, &viewport, new_zoom);
+        }
+
+        if new_zoom == self.target_zoom {
+            self.zoom_anchor = None;
+        }
+
+        self.camera.zoom = new_zoom;
+    }
+
+    fn clamp_to_bounds(&mut self) {
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+
+        let half = self.camera.half_extent();
+
+        for axis in 0..2 {
+            let (lo, hi) = (min[axis] + half[axis], max[axis] - half[axis]);
+            self.position[axis] = if lo > hi {
+                (min[axis] + max[axis]) * 0.5
+            } else {
+                self.position[axis].clamp(lo, hi)
+            };
+        }
+    }
+
+    fn shake_offset(&mut self, dt: f32) -> Vec2 {
+        if self.shake_time <= 0.0 {
+            self.shake_intensity = 0.0;
+            return Vec2::ZERO;
+        }
+
+        self.shake_time -= dt;
+        let strength = self.shake_intensity * (self.shake_time / self.shake_duration).max(0.0);
+
+        Vec2::new(self.next_random(), self.next_random()) * strength
+    }
+
+    /// xorshift 伪随机数，范围 [-1, 1]
+    fn next_random(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+impl Layer for Camera2DController {
+    fn on_ready(&mut self, _renderer: &mut Renderer) {}
+
+    fn on_update(&mut self, delta: &DeltaTime, input: &mut InputState) {
+        let dt = delta.as_seconds() as f32;
+        let cur_pos = input.mouse_pos();
+
+        match self.follow {
+            Some(target) => {
+                let t = 1.0 - (-self.follow_damping * dt).exp();
+                self.position += (target - self.position) * t;
+            }
+            None => {
+                let mut direction = Vec2::ZERO;
+                if input.is_key_pressed(self.bindings.up) {
+                    direction.y += 1.0;
+                }
+                if input.is_key_pressed(self.bindings.down) {
+                    direction.y -= 1.0;
+                }
+                if input.is_key_pressed(self.bindings.left) {
+                    direction.x -= 1.0;
+                }
+                if input.is_key_pressed(self.bindings.right) {
+                    direction.x += 1.0;
+                }
+
+                // 视口高度为 2 * zoom，缩放后平移手感不变
+                self.position += direction.normalize_or_zero() * self.move_speed * 2.0 * self.camera.zoom * dt;
+
+                // 拖动时光标下的世界坐标保持不动，跨过视图边界时按当前所在视图换算
+                if input.is_mouse_pressed(self.bindings.drag) {
+                    let viewport = self.viewport_at(cur_pos);
+                    let zoom = self.camera.zoom;
+                    self.position += self.screen_offset(self.mouse_pos, &viewport, zoom) - self.screen_offset(cur_pos, &viewport, zoom);
+                }
+            }
+        }
+        self.mouse_pos = cur_pos;
+
+        self.update_zoom(dt);
+        self.clamp_to_bounds();
+
+        self.camera.position = self.position + self.shake_offset(dt);
+        self.camera.update();
+    }
+
+    fn on_render(&mut self, renderer: &mut Renderer, _map: &mut ImageBufferManager) {
+        let index = renderer.current_view();
+        if self.view.is_some_and(|view| view != index) {
+            return;
+        }
+
+        // 分屏时每个视图的宽高比不同，记录视图区域供光标换算
+        let viewport = renderer.view_viewport(index);
+        self.views.resize(renderer.views().len(), self.viewport);
+        self.views[index] = ScreenViewport {
+            offset: Vec2::from(viewport.offset),
+            size: Vec2::from(viewport.extent),
+            scale_factor: self.viewport.scale_factor,
+        };
+        self.camera.set_aspect_ratio(viewport.extent[0] / viewport.extent[1]);
+        self.camera.update();
+
+        renderer.update_camera(*self.camera.get_view_projection_matrix());
+    }
+
+    fn on_imgui_render(&mut self, ui: &mut Ui) {
+        ui.window("相机控制器")
+            .size([300.0, 100.0], Condition::FirstUseEver)
+            .build(|| {
+                ui.slider("移动速度", 0.1, 5.0, &mut self.move_speed);
+                ui.slider("缩放步长", 0.01, 0.5, &mut self.zoom_step);
+                ui.slider("缩放平滑", 1.0, 30.0, &mut self.zoom_smoothing);
+            });
+    }
+
+    fn on_physics_update(&mut self, _delta: &DeltaTime) {}
+
+    fn on_event(&mut self, event: &Event) {
+        match event.event {
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    // 约 20 像素算一格
+                    MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
+                };
+
+                self.zoom_by(lines);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_close(&mut self) {}
+
+    fn on_resize(&mut self, event: &ResizeEvent) {
+        self.viewport = event.scaling.viewport;
+        // 视图区域在下一次 on_render 时重新记录
+        self.views.clear();
+        self.camera.apply_scaling(&event.scaling);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> Camera2DController {
+        let mut camera = Camera2D::new(16.0 / 9.0, 2.0, -1.0, 1.0, Vec2::ZERO);
+        camera.update();
+
+        let mut controller = Camera2DController::new(camera);
+        controller.viewport = ScreenViewport::new(Vec2::new(1600.0, 900.0), 1.0);
+        controller
+    }
+
+    /// 左右分屏，各 800 x 900
+    fn split_views() -> Vec<ScreenViewport> {
+        let left = ScreenViewport::new(Vec2::new(800.0, 900.0), 1.0);
+        let right = ScreenViewport {
+            offset: Vec2::new(800.0, 0.0),
+            ..left
+        };
+        vec![left, right]
+    }
+
+    /// 屏幕坐标对应的世界坐标
+    fn world_at(controller: &Camera2DController, screen: (f64, f64)) -> Vec2 {
+        let viewport = controller.viewport_at(screen);
+        controller.position + controller.screen_offset(screen, &viewport, controller.camera.zoom)
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!(a.abs_diff_eq(b, 1e-3), "{a} != {b}");
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        let mut controller = controller();
+        controller.min_zoom = 0.5;
+        controller.max_zoom = 4.0;
+
+        controller.set_zoom(100.0);
+        assert_eq!(controller.target_zoom, 4.0);
+        controller.set_zoom(0.0);
+        assert_eq!(controller.target_zoom, 0.5);
+
+        for _ in 0..100 {
+            controller.zoom_by(3.0);
+        }
+        assert_eq!(controller.target_zoom, 0.5);
+        for _ in 0..100 {
+            controller.zoom_by(-3.0);
+        }
+        assert_eq!(controller.target_zoom, 4.0);
+    }
+
+    #[test]
+    fn position_is_clamped_to_bounds() {
+        // 可见范围半宽 2 * 16 / 9，半高 2
+        let mut bounded = controller().with_bounds(Vec2::new(-10.0, -10.0), Vec2::new(10.0, 10.0));
+        bounded.set_position(Vec2::new(100.0, -100.0));
+        bounded.clamp_to_bounds();
+        assert_near(bounded.position(), Vec2::new(10.0 - 32.0 / 9.0, -8.0));
+
+        // 可见范围大于边界时居中
+        let mut narrow = controller().with_bounds(Vec2::new(0.0, 0.0), Vec2::new(2.0, 10.0));
+        narrow.set_position(Vec2::new(5.0, 3.0));
+        narrow.clamp_to_bounds();
+        assert_near(narrow.position(), Vec2::new(1.0, 3.0));
+    }
+
+    #[test]
+    fn zoom_keeps_world_point_under_cursor() {
+        let mut controller = controller();
+        controller.mouse_pos = (1200.0, 225.0);
+        let before = world_at(&controller, controller.mouse_pos);
+
+        controller.zoom_by(2.0);
+        // 足够长的时间步直接到达目标缩放
+        controller.update_zoom(10.0);
+
+        assert_eq!(controller.camera.zoom, controller.target_zoom);
+        assert!(controller.zoom_anchor.is_none());
+        assert_near(world_at(&controller, (1200.0, 225.0)), before);
+    }
+
+    #[test]
+    fn split_screen_maps_cursor_in_view_under_it() {
+        let mut controller = controller();
+        controller.views = split_views();
+
+        // 右侧视图的中心就是相机中心
+        assert_eq!(controller.viewport_at((1200.0, 450.0)), controller.views[1]);
+        assert_near(world_at(&controller, (1200.0, 450.0)), Vec2::ZERO);
+
+        // 视图宽高比为 8 / 9，右边缘的世界 x 为半高 * 8 / 9
+        let edge = controller.screen_offset((1600.0, 450.0), &controller.views[1], controller.camera.zoom);
+        assert_near(edge, Vec2::new(16.0 / 9.0, 0.0));
+
+        controller.mouse_pos = (1000.0, 300.0);
+        let before = world_at(&controller, controller.mouse_pos);
+        controller.zoom_by(-2.0);
+        controller.update_zoom(10.0);
+        assert_near(world_at(&controller, (1000.0, 300.0)), before);
+
+        // 只控制左侧视图时总按左侧视图换算
+        controller.view = Some(0);
+        assert_eq!(controller.viewport_at((1200.0, 450.0)), controller.views[0]);
+    }
+}
@@ -1,37 +1,137 @@
-use crate::renderer::camera::Camera;
-use glam::{Mat4, Vec3};
+use crate::renderer::camera::scaling::Scaling;
+use crate::renderer::camera::{Camera, ScreenViewport};
+use glam::{EulerRot, Mat4, Quat, Vec2, Vec3};
 
+/// 从相机发出的射线
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// 单位向量
+    pub direction: Vec3,
+}
+
+impl Ray {
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// 与经过 `point`、法线为 `normal` 的平面的交点，平行或交点在射线后方时返回 None
+    pub fn intersect_plane(&self, point: Vec3, normal: Vec3) -> Option<Vec3> {
+        let denom = normal.dot(self.direction);
+        if denom.abs() < 1e-6 {
+            return None;
+        }
+
+        let t = normal.dot(point - self.origin) / denom;
+        (t >= 0.0).then(|| self.at(t))
+    }
+}
+
+/// 透视相机，局部坐标系中看向 -Z、上方为 +Y
 pub struct Camera3D {
     proj: Mat4,
     view: Mat4,
     view_proj: Mat4,
     pub position: Vec3,
-    pub rotation: Vec3,
+    /// 相机朝向，设置了 `target` 时每次 `update` 都会被覆盖
+    pub rotation: Quat,
+    /// 注视点，Some 时相机始终看向该点
+    pub target: Option<Vec3>,
     pub fov: f32,
     pub aspect_ratio: f32,
     pub z_near: f32,
     pub z_far: f32,
+    /// 缩放策略给出的可见高度倍数
+    view_scale: f32,
 }
 
 impl Camera3D {
     pub fn new(fov: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Self {
-        let proj = Mat4::perspective_rh(fov, aspect_ratio, z_near, z_far);
-        let position = Vec3::new(0.0,0.0,0.0);
-        let view = Mat4::look_at_rh(position, Vec3::new(0.0,0.0,0.0), Vec3::new(0.0,1.0,0.0));
-        let rotation = Vec3::new(0.0,0.0,0.0);
-
-        Self {
-            proj,
-            view,
-            view_proj: proj * view,
-            position,
-            rotation,
+        let mut camera = Self {
+            proj: Mat4::IDENTITY,
+            view: Mat4::IDENTITY,
+            view_proj: Mat4::IDENTITY,
+            position: Vec3::new(0.0, 0.0, 5.0),
+            rotation: Quat::IDENTITY,
+            target: None,
             fov,
             aspect_ratio,
             z_near,
-            z_far
+            z_far,
+            view_scale: 1.0,
+        };
+
+        camera.update();
+        camera
+    }
+
+    /// 窗口尺寸变化后应用缩放策略的结果
+    pub fn apply_scaling(&mut self, scaling: &Scaling) {
+        self.aspect_ratio = scaling.aspect_ratio;
+        self.view_scale = scaling.view_scale;
+    }
+
+    /// 按偏航（绕 Y）、俯仰（绕 X）、翻滚（绕 Z）设置朝向，单位为弧度
+    pub fn set_yaw_pitch_roll(&mut self, yaw: f32, pitch: f32, roll: f32) {
+        self.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll);
+    }
+
+    /// 当前朝向的偏航、俯仰、翻滚
+    pub fn yaw_pitch_roll(&self) -> (f32, f32, f32) {
+        self.rotation.to_euler(EulerRot::YXZ)
+    }
+
+    /// 立即转向 `target`，之后不再跟随；需要持续跟随时设置 `target` 字段
+    pub fn look_at(&mut self, target: Vec3) {
+        if let Some(rotation) = Self::look_rotation(self.position, target) {
+            self.rotation = rotation;
+        }
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    /// 经过屏幕物理像素坐标的射线，起点在近平面上，用于拾取
+    pub fn screen_ray(&self, screen: Vec2, viewport: &ScreenViewport) -> Ray {
+        let ndc = viewport.screen_to_ndc(screen);
+        let inverse = self.view_proj.inverse();
+
+        // Vulkan 深度范围为 [0, 1]
+        let near = inverse.project_point3(ndc.extend(0.0));
+        let far = inverse.project_point3(ndc.extend(1.0));
+
+        Ray {
+            origin: near,
+            direction: (far - near).normalize_or(self.forward()),
         }
     }
+
+    /// 从 `position` 看向 `target` 的朝向，两点重合时返回 None
+    fn look_rotation(position: Vec3, target: Vec3) -> Option<Quat> {
+        let forward = (target - position).try_normalize()?;
+
+        // 竖直看时世界 Y 轴与视线平行，改用 Z 轴作为参考上方向
+        let up = if forward.cross(Vec3::Y).length_squared() < 1e-6 { Vec3::Z } else { Vec3::Y };
+
+        let view = Mat4::look_to_rh(position, forward, up);
+        Some(Quat::from_mat4(&view.inverse()).normalize())
+    }
+
+    /// Vulkan 裁剪空间 y 轴向下，与 Camera2D 一样翻转 y
+    fn get_projection_matrix(fov: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Mat4 {
+        let mut m = Mat4::perspective_rh(fov, aspect_ratio, z_near, z_far);
+        m.y_axis.y *= -1.0;
+        m
+    }
 }
 
 impl Camera for Camera3D {
@@ -48,8 +148,71 @@ impl Camera for Camera3D {
     }
 
     fn update(&mut self) {
-        self.proj = Mat4::perspective_rh(self.fov, self.aspect_ratio, self.z_near, self.z_far);
-        self.view = Mat4::look_at_rh(self.position, Vec3::new(0.0,0.0,0.0), Vec3::new(0.0,1.0,0.0));
+        if let Some(rotation) = self.target.and_then(|target| Self::look_rotation(self.position, target)) {
+            self.rotation = rotation;
+        }
+
+        // Fill 策略下裁掉上下部分，等价于缩小垂直视野
+        let fov = 2.0 * ((self.fov * 0.5).tan() * self.view_scale).atan();
+
+        self.proj = Self::get_projection_matrix(fov, self.aspect_ratio, self.z_near, self.z_far);
+        self.view = Mat4::from_rotation_translation(self.rotation, self.position).inverse();
         self.view_proj = self.proj * self.view;
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> Camera3D {
+        let mut camera = Camera3D::new(60f32.to_radians(), 16.0 / 9.0, 0.1, 100.0);
+        camera.position = Vec3::new(1.0, 2.0, 3.0);
+        camera.set_yaw_pitch_roll(0.3, -0.2, 0.0);
+        camera.update();
+        camera
+    }
+
+    fn viewport() -> ScreenViewport {
+        ScreenViewport {
+            offset: Vec2::new(10.0, 20.0),
+            size: Vec2::new(1280.0, 720.0),
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn centre_ray_points_forward() {
+        let camera = camera();
+        let viewport = viewport();
+        let centre = viewport.offset + viewport.size * 0.5;
+
+        let ray = camera.screen_ray(centre, &viewport);
+        assert!(ray.direction.abs_diff_eq(camera.forward(), 1e-4), "{} != {}", ray.direction, camera.forward());
+        assert!(ray.origin.abs_diff_eq(camera.position + camera.forward() * 0.1, 1e-3), "{}", ray.origin);
+
+        let ahead = camera.world_to_screen(camera.position + camera.forward() * 10.0, &viewport).unwrap();
+        assert!(ahead.abs_diff_eq(centre, 1e-2), "{ahead} != {centre}");
+    }
+
+    #[test]
+    fn screen_world_round_trip_with_offset_viewport() {
+        let camera = camera();
+        let viewport = viewport();
+
+        for screen in [Vec2::new(10.0, 20.0), Vec2::new(400.0, 600.0), Vec2::new(1289.0, 739.0)] {
+            let world = camera.screen_to_world(screen, &viewport);
+            let back = camera.world_to_screen(world, &viewport).unwrap();
+            assert!(back.abs_diff_eq(screen, 1e-2), "{back} != {screen}");
+        }
+    }
+
+    #[test]
+    fn point_behind_camera_has_no_screen_position() {
+        let camera = camera();
+        let behind = camera.position - camera.forward() * 5.0;
+
+        assert_eq!(camera.world_to_screen(behind, &viewport()), None);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::core::delta_time::DeltaTime;
+use crate::core::event::{Event, ResizeEvent};
+use crate::core::input::InputState;
+use crate::core::layer::Layer;
+use crate::renderer::camera::camera3d::Camera3D;
+use crate::renderer::camera::Camera;
+use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::renderer::Renderer;
+use glam::Vec3;
+use imgui::{Condition, Ui};
+use winit::event::{MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::KeyCode;
+
+/// 俯仰角限制，避免越过头顶后翻转
+const MAX_PITCH: f32 = 89.0f32.to_radians();
+
+/// 自由飞行相机：WASD 平移、Q/E 升降、按住右键拖动转向、滚轮前后推拉
+pub struct FlyCameraController {
+    pub camera: Camera3D,
+    /// 每秒移动距离
+    pub move_speed: f32,
+    /// 每像素转动的弧度
+    pub look_sensitivity: f32,
+    /// 每格滚轮推拉的距离
+    pub dolly_speed: f32,
+    /// 只为该分屏视图提供相机，None 表示所有视图
+    pub view: Option<usize>,
+    yaw: f32,
+    pitch: f32,
+    mouse_pos: (f64, f64),
+}
+
+impl FlyCameraController {
+    pub fn new(camera: Camera3D) -> Self {
+        let (yaw, pitch, _) = camera.yaw_pitch_roll();
+
+        Self {
+            camera,
+            move_speed: 5.0,
+            look_sensitivity: 0.003,
+            dolly_speed: 0.5,
+            view: None,
+            yaw,
+            pitch,
+            mouse_pos: (0.0, 0.0),
+        }
+    }
+}
+
+impl Layer for FlyCameraController {
+    fn on_ready(&mut self, _renderer: &mut Renderer) {
+        // 控制器自己管理朝向
+        self.camera.target = None;
+    }
+
+    fn on_update(&mut self, delta: &DeltaTime, input: &mut InputState) {
+        let cur_pos = input.mouse_pos();
+
+        if input.is_mouse_pressed(MouseButton::Right) {
+            let dx = (cur_pos.0 - self.mouse_pos.0) as f32;
+            let dy = (cur_pos.1 - self.mouse_pos.1) as f32;
+
+            self.yaw -= dx * self.look_sensitivity;
+            self.pitch = (self.pitch - dy * self.look_sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+        self.mouse_pos = cur_pos;
+
+        self.camera.set_yaw_pitch_roll(self.yaw, self.pitch, 0.0);
+
+        let mut direction = Vec3::ZERO;
+        if input.is_key_pressed(KeyCode::KeyW) {
+            direction += self.camera.forward();
+        }
+        if input.is_key_pressed(KeyCode::KeyS) {
+            direction -= self.camera.forward();
+        }
+        if input.is_key_pressed(KeyCode::KeyD) {
+            direction += self.camera.right();
+        }
+        if input.is_key_pressed(KeyCode::KeyA) {
+            direction -= self.camera.right();
+        }
+        if input.is_key_pressed(KeyCode::KeyE) {
+            direction += Vec3::Y;
+        }
+        if input.is_key_pressed(KeyCode::KeyQ) {
+            direction -= Vec3::Y;
+        }
+
+        self.camera.position += direction.normalize_or_zero() * self.move_speed * delta.as_seconds() as f32;
+        self.camera.update();
+    }
+
+    fn on_render(&mut self, renderer: &mut Renderer, _map: &mut ImageBufferManager) {
+        if self.view.is_some_and(|view| view != renderer.current_view()) {
+            return;
+        }
+
+        // 分屏时每个视图的宽高比不同
+        let viewport = renderer.view_viewport(renderer.current_view());
+        self.camera.aspect_ratio = viewport.extent[0] / viewport.extent[1];
+        self.camera.update();
+
+        renderer.update_camera(*self.camera.get_view_projection_matrix());
+    }
+
+    fn on_imgui_render(&mut self, ui: &mut Ui) {
+        ui.window("飞行相机")
+            .size([300.0, 120.0], Condition::FirstUseEver)
+            .build(|| {
+                ui.slider("移动速度", 0.5, 50.0, &mut self.move_speed);
+                ui.slider("转向灵敏度", 0.0005, 0.01, &mut self.look_sensitivity);
+                ui.slider("推拉距离", 0.1, 5.0, &mut self.dolly_speed);
+            });
+    }
+
+    fn on_physics_update(&mut self, _delta: &DeltaTime) {}
+
+    fn on_event(&mut self, event: &Event) {
+        if let WindowEvent::MouseWheel { delta, .. } = event.event {
+            let lines = match delta {
+                MouseScrollDelta::LineDelta(_, y) => *y,
+                // 约 20 像素算一格
+                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
+            };
+
+            self.camera.position += self.camera.forward() * lines * self.dolly_speed;
+        }
+    }
+
+    fn on_close(&mut self) {}
+
+    fn on_resize(&mut self, event: &ResizeEvent) {
+        self.camera.apply_scaling(&event.scaling);
+    }
+}
@@ -1,7 +1,62 @@
-use glam::Mat4;
+use crate::core::input::InputState;
+use glam::{Mat4, Vec2, Vec3};
 
 pub mod camera2d;
+pub mod camera2d_controller;
 pub mod camera3d;
+pub mod fly_camera_controller;
+pub mod orbit_camera_controller;
+pub mod scaling;
+
+/// 相机渲染到的屏幕区域，用于屏幕坐标与世界坐标互相转换
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScreenViewport {
+    /// 区域左上角，物理像素
+    pub offset: Vec2,
+    /// 区域尺寸，物理像素
+    pub size: Vec2,
+    /// DPI 缩放，物理像素 = 逻辑像素 * scale_factor
+    pub scale_factor: f32,
+}
+
+impl ScreenViewport {
+    pub fn new(size: Vec2, scale_factor: f32) -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            size,
+            scale_factor,
+        }
+    }
+
+    /// 整个窗口
+    pub fn from_input(input: &InputState) -> Self {
+        let (width, height) = input.window_size();
+        Self::new(Vec2::new(width as f32, height as f32), input.scale_factor() as f32)
+    }
+
+    /// 物理像素坐标转换到 NDC，Vulkan 的 NDC 与屏幕一样 y 轴向下
+    pub fn screen_to_ndc(&self, screen: Vec2) -> Vec2 {
+        (screen - self.offset) / self.size.max(Vec2::ONE) * 2.0 - Vec2::ONE
+    }
+
+    /// 物理像素坐标是否落在区域内
+    pub fn contains(&self, screen: Vec2) -> bool {
+        let local = screen - self.offset;
+        local.cmpge(Vec2::ZERO).all() && local.cmplt(self.size).all()
+    }
+
+    pub fn ndc_to_screen(&self, ndc: Vec2) -> Vec2 {
+        self.offset + (ndc + Vec2::ONE) * 0.5 * self.size
+    }
+
+    pub fn logical_to_physical(&self, logical: Vec2) -> Vec2 {
+        logical * self.scale_factor
+    }
+
+    pub fn physical_to_logical(&self, physical: Vec2) -> Vec2 {
+        physical / self.scale_factor
+    }
+}
 
 pub trait Camera {
     fn get_projection_matrix(&self) -> &Mat4;
@@ -9,4 +64,23 @@ pub trait Camera {
     fn get_view_projection_matrix(&self) -> &Mat4;
 
     fn update(&mut self);
-}
\ No newline at end of file
+
+    /// 屏幕物理像素坐标对应的世界坐标，位于近平面上
+    ///
+    /// 投影矩阵中的 y 轴翻转已包含在逆矩阵中，传入的坐标无需翻转
+    fn screen_to_world(&self, screen: Vec2, viewport: &ScreenViewport) -> Vec3 {
+        let ndc = viewport.screen_to_ndc(screen);
+        self.get_view_projection_matrix().inverse().project_point3(ndc.extend(0.0))
+    }
+
+    /// 世界坐标投影到屏幕物理像素坐标，位于相机后方时返回 None
+    fn world_to_screen(&self, world: Vec3, viewport: &ScreenViewport) -> Option<Vec2> {
+        let clip = *self.get_view_projection_matrix() * world.extend(1.0);
+
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        Some(viewport.ndc_to_screen(clip.truncate().truncate() / clip.w))
+    }
+}
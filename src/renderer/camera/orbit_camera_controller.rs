@@ -0,0 +1,150 @@
+use crate::core::delta_time::DeltaTime;
+use crate::core::event::{Event, ResizeEvent};
+use crate::core::input::InputState;
+use crate::core::layer::Layer;
+use crate::renderer::camera::camera3d::Camera3D;
+use crate::renderer::camera::Camera;
+use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::renderer::Renderer;
+use glam::{EulerRot, Quat, Vec3};
+use imgui::{Condition, Ui};
+use winit::event::{MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::KeyCode;
+
+/// 俯仰角限制，避免越过头顶后翻转
+const MAX_PITCH: f32 = 89.0f32.to_radians();
+
+/// 环绕相机：按住左键拖动绕目标旋转、WASD 在水平面内移动目标、滚轮推拉距离
+pub struct OrbitCameraController {
+    pub camera: Camera3D,
+    /// 环绕中心
+    pub target: Vec3,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// 每像素转动的弧度
+    pub orbit_sensitivity: f32,
+    /// 每秒移动目标的距离
+    pub pan_speed: f32,
+    /// 每格滚轮缩放距离的比例
+    pub dolly_speed: f32,
+    /// 只为该分屏视图提供相机，None 表示所有视图
+    pub view: Option<usize>,
+    yaw: f32,
+    pitch: f32,
+    mouse_pos: (f64, f64),
+}
+
+impl OrbitCameraController {
+    pub fn new(camera: Camera3D, target: Vec3, distance: f32) -> Self {
+        Self {
+            camera,
+            target,
+            distance,
+            min_distance: 0.5,
+            max_distance: 100.0,
+            orbit_sensitivity: 0.005,
+            pan_speed: 5.0,
+            dolly_speed: 0.1,
+            view: None,
+            yaw: 0.0,
+            pitch: -0.3,
+            mouse_pos: (0.0, 0.0),
+        }
+    }
+
+    /// 根据角度和距离放置相机
+    fn apply(&mut self) {
+        let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
+
+        self.camera.target = None;
+        self.camera.rotation = rotation;
+        self.camera.position = self.target + rotation * Vec3::Z * self.distance;
+        self.camera.update();
+    }
+}
+
+impl Layer for OrbitCameraController {
+    fn on_ready(&mut self, _renderer: &mut Renderer) {
+        self.apply();
+    }
+
+    fn on_update(&mut self, delta: &DeltaTime, input: &mut InputState) {
+        let cur_pos = input.mouse_pos();
+
+        if input.is_mouse_pressed(MouseButton::Left) {
+            let dx = (cur_pos.0 - self.mouse_pos.0) as f32;
+            let dy = (cur_pos.1 - self.mouse_pos.1) as f32;
+
+            self.yaw -= dx * self.orbit_sensitivity;
+            self.pitch = (self.pitch - dy * self.orbit_sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+        self.mouse_pos = cur_pos;
+
+        // 目标在水平面内沿相机朝向移动
+        let yaw_rotation = Quat::from_rotation_y(self.yaw);
+        let forward = yaw_rotation * Vec3::NEG_Z;
+        let right = yaw_rotation * Vec3::X;
+
+        let mut direction = Vec3::ZERO;
+        if input.is_key_pressed(KeyCode::KeyW) {
+            direction += forward;
+        }
+        if input.is_key_pressed(KeyCode::KeyS) {
+            direction -= forward;
+        }
+        if input.is_key_pressed(KeyCode::KeyD) {
+            direction += right;
+        }
+        if input.is_key_pressed(KeyCode::KeyA) {
+            direction -= right;
+        }
+
+        self.target += direction.normalize_or_zero() * self.pan_speed * delta.as_seconds() as f32;
+        self.apply();
+    }
+
+    fn on_render(&mut self, renderer: &mut Renderer, _map: &mut ImageBufferManager) {
+        if self.view.is_some_and(|view| view != renderer.current_view()) {
+            return;
+        }
+
+        // 分屏时每个视图的宽高比不同
+        let viewport = renderer.view_viewport(renderer.current_view());
+        self.camera.aspect_ratio = viewport.extent[0] / viewport.extent[1];
+        self.camera.update();
+
+        renderer.update_camera(*self.camera.get_view_projection_matrix());
+    }
+
+    fn on_imgui_render(&mut self, ui: &mut Ui) {
+        ui.window("环绕相机")
+            .size([300.0, 120.0], Condition::FirstUseEver)
+            .build(|| {
+                ui.slider("距离", self.min_distance, self.max_distance, &mut self.distance);
+                ui.slider("旋转灵敏度", 0.001, 0.02, &mut self.orbit_sensitivity);
+                ui.slider("移动速度", 0.5, 50.0, &mut self.pan_speed);
+            });
+    }
+
+    fn on_physics_update(&mut self, _delta: &DeltaTime) {}
+
+    fn on_event(&mut self, event: &Event) {
+        if let WindowEvent::MouseWheel { delta, .. } = event.event {
+            let lines = match delta {
+                MouseScrollDelta::LineDelta(_, y) => *y,
+                // 约 20 像素算一格
+                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
+            };
+
+            self.distance = (self.distance * (1.0 - lines * self.dolly_speed))
+                .clamp(self.min_distance, self.max_distance);
+        }
+    }
+
+    fn on_close(&mut self) {}
+
+    fn on_resize(&mut self, event: &ResizeEvent) {
+        self.camera.apply_scaling(&event.scaling);
+    }
+}
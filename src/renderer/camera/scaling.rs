@@ -0,0 +1,149 @@
+use crate::renderer::camera::ScreenViewport;
+use glam::Vec2;
+
+/// 窗口尺寸变化时相机与视口的缩放策略
+///
+/// `width` / `height` 为设计分辨率（物理像素）
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ScalingPolicy {
+    /// 可见高度固定，宽度随窗口宽高比变化，视口铺满窗口
+    #[default]
+    FixedHeight,
+    /// 完整显示设计区域，等比缩放，多余部分留黑边
+    Fit { width: u32, height: u32 },
+    /// 等比缩放铺满窗口，设计区域超出窗口的部分被裁掉
+    Fill { width: u32, height: u32 },
+    /// 设计分辨率按整数倍放大，保证像素清晰，多余部分留黑边
+    PixelPerfect { width: u32, height: u32 },
+}
+
+/// 缩放策略作用于某个窗口尺寸的结果
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Scaling {
+    /// 实际渲染的区域，黑边在区域之外
+    pub viewport: ScreenViewport,
+    /// 相机应使用的宽高比
+    pub aspect_ratio: f32,
+    /// 可见高度相对相机缩放的倍数，只有 Fill 会小于 1
+    pub view_scale: f32,
+}
+
+impl ScalingPolicy {
+    /// 计算窗口尺寸（物理像素）下的视口和相机参数
+    pub fn apply(&self, window_size: [u32; 2], scale_factor: f32) -> Scaling {
+        let window = Vec2::new(window_size[0].max(1) as f32, window_size[1].max(1) as f32);
+        let window_aspect = window.x / window.y;
+
+        let (size, aspect_ratio, view_scale) = match *self {
+            ScalingPolicy::FixedHeight => (window, window_aspect, 1.0),
+            ScalingPolicy::Fit { width, height } => {
+                let design = Self::design_size(width, height);
+                let scale = (window / design).min_element();
+                (design * scale, design.x / design.y, 1.0)
+            }
+            ScalingPolicy::Fill { width, height } => {
+                let design = Self::design_size(width, height);
+                let design_aspect = design.x / design.y;
+                // 窗口更宽时宽度对齐设计区域，上下被裁掉
+                let view_scale = (design_aspect / window_aspect).min(1.0);
+                (window, window_aspect, view_scale)
+            }
+            ScalingPolicy::PixelPerfect { width, height } => {
+                let design = Self::design_size(width, height);
+                let scale = (window / design).min_element().floor().max(1.0);
+                (design * scale, design.x / design.y, 1.0)
+            }
+        };
+
+        // 居中，偏移取整避免采样落在半像素上
+        let offset = ((window - size) * 0.5).max(Vec2::ZERO).floor();
+
+        Scaling {
+            viewport: ScreenViewport {
+                offset,
+                size,
+                scale_factor,
+            },
+            aspect_ratio,
+            view_scale,
+        }
+    }
+
+    fn design_size(width: u32, height: u32) -> Vec2 {
+        Vec2::new(width.max(1) as f32, height.max(1) as f32)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_letterbox_on_taller_window() {
+        let scaling = ScalingPolicy::Fit { width: 800, height: 600 }.apply([800, 800], 1.0);
+
+        assert_eq!(scaling.viewport.size, Vec2::new(800.0, 600.0));
+        assert_eq!(scaling.viewport.offset, Vec2::new(0.0, 100.0));
+        assert_eq!(scaling.aspect_ratio, 800.0 / 600.0);
+        assert_eq!(scaling.view_scale, 1.0);
+    }
+
+    #[test]
+    fn fit_pillarbox_on_wider_window() {
+        let scaling = ScalingPolicy::Fit { width: 800, height: 600 }.apply([1000, 600], 2.0);
+
+        assert_eq!(scaling.viewport.size, Vec2::new(800.0, 600.0));
+        assert_eq!(scaling.viewport.offset, Vec2::new(100.0, 0.0));
+        assert_eq!(scaling.viewport.scale_factor, 2.0);
+    }
+
+    #[test]
+    fn fill_shrinks_view_on_wider_window() {
+        let policy = ScalingPolicy::Fill { width: 800, height: 600 };
+
+        // 窗口宽高比是设计区域的两倍，可见高度减半
+        let wide = policy.apply([1600, 600], 1.0);
+        assert_eq!(wide.viewport.size, Vec2::new(1600.0, 600.0));
+        assert_eq!(wide.viewport.offset, Vec2::ZERO);
+        assert_eq!(wide.aspect_ratio, 1600.0 / 600.0);
+        assert_eq!(wide.view_scale, 0.5);
+
+        // 窗口更高时左右被裁掉，高度不变
+        let tall = policy.apply([800, 800], 1.0);
+        assert_eq!(tall.view_scale, 1.0);
+    }
+
+    #[test]
+    fn pixel_perfect_floors_scale() {
+        let policy = ScalingPolicy::PixelPerfect { width: 320, height: 180 };
+
+        let scaling = policy.apply([1000, 600], 1.0);
+        assert_eq!(scaling.viewport.size, Vec2::new(960.0, 540.0));
+        assert_eq!(scaling.viewport.offset, Vec2::new(20.0, 30.0));
+
+        // 窗口比设计分辨率小时至少按 1 倍显示，不出现负偏移
+        let scaling = policy.apply([200, 100], 1.0);
+        assert_eq!(scaling.viewport.size, Vec2::new(320.0, 180.0));
+        assert_eq!(scaling.viewport.offset, Vec2::ZERO);
+    }
+
+    #[test]
+    fn zero_sized_window_stays_finite() {
+        let policies = [
+            ScalingPolicy::FixedHeight,
+            ScalingPolicy::Fit { width: 800, height: 600 },
+            ScalingPolicy::Fill { width: 800, height: 600 },
+            ScalingPolicy::PixelPerfect { width: 320, height: 180 },
+        ];
+
+        for policy in policies {
+            let scaling = policy.apply([0, 0], 1.0);
+
+            assert!(scaling.viewport.size.is_finite() && scaling.viewport.size.cmpgt(Vec2::ZERO).all(), "{policy:?}");
+            assert!(scaling.viewport.offset.is_finite(), "{policy:?}");
+            assert!(scaling.aspect_ratio.is_finite() && scaling.aspect_ratio > 0.0, "{policy:?}");
+            assert!(scaling.view_scale > 0.0 && scaling.view_scale <= 1.0, "{policy:?}");
+        }
+    }
+}
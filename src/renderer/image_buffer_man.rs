@@ -1,14 +1,19 @@
 use crate::renderer::frame_commands::FrameCommands;
 use log::info;
+use smallvec::smallvec;
 use std::sync::Arc;
 use vulkano::buffer::Subbuffer;
-use vulkano::command_buffer::CopyBufferToImageInfo;
+use vulkano::command_buffer::{BufferImageCopy, CopyBufferToImageInfo};
 use vulkano::image::Image;
 
 
 pub struct ImageAndBuffer {
     pub image: Arc<Image>,
     pub buffer: Option<Subbuffer<[u8]>>,
+    /// 写入图像的区域左上角
+    pub offset: [u32; 2],
+    /// 写入图像的区域尺寸
+    pub extent: [u32; 2],
 }
 
 #[derive(Default)]
@@ -19,8 +24,14 @@ pub struct ImageBufferManager {
 impl ImageBufferManager {
 
     pub fn add(&mut self, image: Arc<Image>, buffer: Subbuffer<[u8]>) {
+        let [width, height, _] = image.extent();
+        self.add_region(image, buffer, [0, 0], [width, height]);
+    }
+
+    /// 只写入图像的一部分，用于向纹理图集中追加图片
+    pub fn add_region(&mut self, image: Arc<Image>, buffer: Subbuffer<[u8]>, offset: [u32; 2], extent: [u32; 2]) {
         info!("push {}", buffer.size());
-        self.items.push(ImageAndBuffer { image, buffer: Some(buffer)  });
+        self.items.push(ImageAndBuffer { image, buffer: Some(buffer), offset, extent });
     }
 
     pub fn copy_all_buffer_to_image(&mut self, frame: &mut FrameCommands) {
@@ -28,12 +39,22 @@ impl ImageBufferManager {
             if let Some(buffer) = item.buffer.take() {
                 info!("copying item {i} into gpu image");
 
+                let region = BufferImageCopy {
+                    image_subresource: item.image.subresource_layers(),
+                    image_offset: [item.offset[0], item.offset[1], 0],
+                    image_extent: [item.extent[0], item.extent[1], 1],
+                    ..BufferImageCopy::default()
+                };
+
                 frame.builder
                     .copy_buffer_to_image(
-                        CopyBufferToImageInfo::buffer_image(
-                            buffer,
-                            item.image.clone()
-                        )
+                        CopyBufferToImageInfo {
+                            regions: smallvec![region],
+                            ..CopyBufferToImageInfo::buffer_image(
+                                buffer,
+                                item.image.clone()
+                            )
+                        }
                     )
                     .expect("copy_buffer_to_image failed");
             }
@@ -43,4 +64,4 @@ impl ImageBufferManager {
     pub fn clear(&mut self) {
         self.items.clear();
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,94 @@
+use crate::asset::handle::Handle;
+use crate::asset::texture::Texture;
+use crate::renderer::blend_mode::BlendMode;
+use crate::renderer::shaders::Shader;
+use std::sync::Arc;
+use vulkano::buffer::Subbuffer;
+use vulkano::descriptor_set::DescriptorSet;
+use vulkano::pipeline::graphics::rasterization::CullMode;
+
+/// 图形管线的可变状态，相同着色器 + 相同状态共享一条缓存的管线
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PipelineState {
+    pub blend_mode: BlendMode,
+    pub cull_mode: CullMode,
+    /// 是否做深度测试，仅在渲染通道带深度附件时生效
+    pub depth_test: bool,
+    pub depth_write: bool,
+}
+
+impl Default for PipelineState {
+    fn default() -> Self {
+        BlendMode::default().into()
+    }
+}
+
+impl From<BlendMode> for PipelineState {
+    /// 2D 绘制的默认状态：不剔除，半透明绘制只做深度测试不写入深度
+    fn from(blend_mode: BlendMode) -> Self {
+        Self {
+            blend_mode,
+            cull_mode: CullMode::None,
+            depth_test: true,
+            depth_write: !blend_mode.is_transparent(),
+        }
+    }
+}
+
+/// 已注册到渲染器的材质
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MaterialHandle(pub u32);
+
+/// 材质：着色器、管线状态、主纹理和自定义 uniform 块
+///
+/// 着色器需与内置精灵着色器使用相同的顶点输入和 `view_proj` 推送常量；
+/// set = 0 binding = 0 为主纹理，set = 1 为材质的 uniform 块
+pub struct Material {
+    /// 注册后只能由渲染器替换（热重载），以保证缓存的管线与之一致
+    pub(crate) shader: Arc<dyn Shader>,
+    /// 注册后通过 `Renderer::set_material_state` 修改，修改前会先校验管线
+    pub(crate) state: PipelineState,
+    pub texture: Option<Handle<Texture>>,
+    pub color: [f32; 4],
+
+    /// set = 1 中各 binding 的 uniform 缓冲
+    pub uniforms: Vec<(u32, Subbuffer<[u8]>)>,
+    /// 由 `uniforms` 生成的描述符集，修改 uniform 后重建
+    pub set: Option<Arc<DescriptorSet>>,
+}
+
+impl Material {
+    pub fn new(shader: Arc<dyn Shader>) -> Self {
+        Self {
+            shader,
+            state: PipelineState::default(),
+            texture: None,
+            color: [1.0, 1.0, 1.0, 1.0],
+            uniforms: Vec::new(),
+            set: None,
+        }
+    }
+
+    pub fn shader(&self) -> &Arc<dyn Shader> {
+        &self.shader
+    }
+
+    pub fn state(&self) -> PipelineState {
+        self.state
+    }
+
+    pub fn with_state(mut self, state: PipelineState) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn with_texture(mut self, texture: Handle<Texture>) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+}
@@ -0,0 +1,68 @@
+use crate::renderer::shapes::mesh::{AzerVertex, Mesh, Mesh3D, Vertex3D};
+use crate::renderer::vertex;
+use std::sync::Arc;
+use vulkano::buffer::{BufferContents, Subbuffer};
+use vulkano::memory::allocator::StandardMemoryAllocator;
+
+/// 已上传网格在共享顶点/索引缓冲中的位置
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MeshHandle {
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub index_count: u32,
+}
+
+/// 所有静态网格共用的顶点/索引缓冲，只在新增网格后重新上传
+pub struct MeshBuffer<V = AzerVertex> {
+    vertices: Vec<V>,
+    indices: Vec<u32>,
+    buffers: Option<(Subbuffer<[V]>, Subbuffer<[u32]>)>,
+    allocator: Arc<StandardMemoryAllocator>,
+}
+
+impl<V: BufferContents + Clone> MeshBuffer<V> {
+    pub fn new(allocator: Arc<StandardMemoryAllocator>) -> Self {
+        Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            buffers: None,
+            allocator,
+        }
+    }
+
+    pub fn add_vertices(&mut self, vertices: &[V], indices: &[u32]) -> MeshHandle {
+        let handle = MeshHandle {
+            first_index: self.indices.len() as u32,
+            vertex_offset: self.vertices.len() as i32,
+            index_count: indices.len() as u32,
+        };
+
+        self.vertices.extend(vertices.iter().cloned());
+        self.indices.extend(indices.iter().copied());
+        self.buffers = None;
+
+        handle
+    }
+
+    /// 获取 GPU 缓冲，有新增网格时重新上传
+    pub fn buffers(&mut self) -> (Subbuffer<[V]>, Subbuffer<[u32]>) {
+        self.buffers
+            .get_or_insert_with(|| (
+                vertex::get_vbo(self.vertices.clone(), self.allocator.clone()),
+                vertex::get_ibo_2d(self.indices.clone(), self.allocator.clone()),
+            ))
+            .clone()
+    }
+}
+
+impl MeshBuffer<AzerVertex> {
+    pub fn add(&mut self, mesh: &Mesh) -> MeshHandle {
+        self.add_vertices(&mesh.vertices, &mesh.indices)
+    }
+}
+
+impl MeshBuffer<Vertex3D> {
+    pub fn add(&mut self, mesh: &Mesh3D) -> MeshHandle {
+        self.add_vertices(&mesh.vertices, &mesh.indices)
+    }
+}
@@ -6,4 +6,11 @@ pub mod renderer3d;
 pub mod frame_commands;
 pub mod shapes;
 pub mod shaders;
-pub mod image_buffer_man;
\ No newline at end of file
+pub mod image_buffer_man;
+pub mod readback;
+pub mod blend_mode;
+pub mod mesh_buffer;
+pub mod upload_ring;
+pub mod material;
+pub mod render_view;
+pub mod render_target;
\ No newline at end of file
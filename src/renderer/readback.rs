@@ -0,0 +1,49 @@
+use crate::api::vulkan_helper;
+use crate::renderer::frame_commands::FrameCommands;
+use image::RgbaImage;
+use std::sync::Arc;
+use vulkano::buffer::Subbuffer;
+use vulkano::command_buffer::CopyImageToBufferInfo;
+use vulkano::image::Image;
+use vulkano::memory::allocator::StandardMemoryAllocator;
+
+/// 一次图像回读：在命令缓冲中记录拷贝，GPU 执行完毕后再读取
+pub struct Readback {
+    buffer: Subbuffer<[u8]>,
+    extent: [u32; 2],
+}
+
+impl Readback {
+    /// 记录从 `image`（R8G8B8A8）到主机可见缓冲区的拷贝
+    pub fn record(
+        frame: &mut FrameCommands,
+        image: Arc<Image>,
+        allocator: Arc<StandardMemoryAllocator>,
+    ) -> Self {
+        let [width, height, _] = image.extent();
+        let buffer = vulkan_helper::get_readback_buffer(
+            width as u64 * height as u64 * 4,
+            allocator
+        );
+
+        frame.builder
+            .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, buffer.clone()))
+            .expect("copy_image_to_buffer failed");
+
+        Self {
+            buffer,
+            extent: [width, height],
+        }
+    }
+
+    pub fn extent(&self) -> [u32; 2] {
+        self.extent
+    }
+
+    /// 读取像素，必须在对应的命令缓冲执行完成后调用
+    pub fn to_image(&self) -> RgbaImage {
+        let pixels = self.buffer.read().expect("读取回读缓冲区失败");
+        RgbaImage::from_raw(self.extent[0], self.extent[1], pixels.to_vec())
+            .expect("回读数据大小与图像尺寸不匹配")
+    }
+}
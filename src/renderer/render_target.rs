@@ -0,0 +1,101 @@
+use crate::api::vulkan_helper;
+use glam::Mat4;
+use imgui::TextureId;
+use std::sync::Arc;
+use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
+use vulkano::descriptor_set::layout::DescriptorSetLayout;
+use vulkano::descriptor_set::{DescriptorSet, WriteDescriptorSet};
+use vulkano::format::Format;
+use vulkano::image::sampler::Sampler;
+use vulkano::image::view::ImageView;
+use vulkano::image::Image;
+use vulkano::memory::allocator::StandardMemoryAllocator;
+use vulkano::pipeline::graphics::viewport::Viewport;
+use vulkano::render_pass::{Framebuffer, RenderPass};
+
+/// `Renderer::create_render_target` 返回的句柄
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RenderTargetHandle(pub u32);
+
+impl RenderTargetHandle {
+    /// 在 ImGui 中显示渲染目标时使用的纹理 ID，0 保留给字体纹理
+    pub fn texture_id(&self) -> TextureId {
+        TextureId::new(self.0 as usize + 1)
+    }
+
+    /// `texture_id` 的逆运算，不是渲染目标时返回 None
+    pub fn from_texture_id(id: TextureId) -> Option<Self> {
+        id.id().checked_sub(1).map(|index| RenderTargetHandle(index as u32))
+    }
+}
+
+/// 离屏渲染目标：有自己的帧缓冲和深度缓冲，渲染结果可以像图片一样被采样
+pub struct RenderTarget {
+    pub image: Arc<Image>,
+    pub image_view: Arc<ImageView>,
+    pub framebuffer: Arc<Framebuffer>,
+    /// 作为精灵纹理采样时的描述符集（set = 0）
+    pub set: Arc<DescriptorSet>,
+    pub clear_color: [f32; 4],
+    /// 绘制到该目标时使用的相机矩阵
+    pub view_proj: [[f32; 4]; 4],
+    /// 本帧是否调用过 `begin_target`，只有用到的目标才会执行渲染通道
+    ///
+    /// 新建的目标初始为 true：图像创建时内容未定义，下一帧先执行一次渲染通道清除为 `clear_color`
+    pub used: bool,
+}
+
+impl RenderTarget {
+    pub fn new(
+        extent: [u32; 2],
+        format: Format,
+        render_pass: Arc<RenderPass>,
+        set_layout: Arc<DescriptorSetLayout>,
+        memory_allocator: Arc<StandardMemoryAllocator>,
+        set_allocator: Arc<StandardDescriptorSetAllocator>,
+        sampler: Arc<Sampler>,
+    ) -> Self {
+        let image = vulkan_helper::get_render_target_image(memory_allocator.clone(), extent, format);
+        let image_view = ImageView::new_default(image.clone()).unwrap();
+
+        // 与主帧缓冲共用渲染通道，已缓存的管线可以直接使用
+        let framebuffer = vulkan_helper::get_framebuffers(
+            vec![image.clone()],
+            render_pass,
+            memory_allocator
+        ).remove(0);
+
+        let set = DescriptorSet::new(
+            set_allocator,
+            set_layout,
+            [WriteDescriptorSet::image_view_sampler(0, image_view.clone(), sampler)],
+            []
+        ).unwrap_or_else(|err| panic!("渲染目标描述符集创建失败: {}", err));
+
+        Self {
+            image,
+            image_view,
+            framebuffer,
+            set,
+            clear_color: [0.0, 0.0, 0.0, 0.0],
+            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            used: true,
+        }
+    }
+
+    pub fn extent(&self) -> [u32; 2] {
+        let [width, height, _] = self.image.extent();
+        [width, height]
+    }
+
+    /// 覆盖整个目标的视口
+    pub fn viewport(&self) -> Viewport {
+        let [width, height] = self.extent();
+
+        Viewport {
+            offset: [0.0, 0.0],
+            extent: [width as f32, height as f32],
+            depth_range: 0.0..=1.0
+        }
+    }
+}
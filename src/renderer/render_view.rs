@@ -0,0 +1,58 @@
+use vulkano::pipeline::graphics::viewport::Viewport;
+
+/// 分屏视图：场景视口中的一块矩形区域
+///
+/// 每帧对每个视图执行一次图层栈的 on_render，每个视图有独立的相机矩阵和绘制列表
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderView {
+    /// 归一化矩形 [x, y, 宽, 高]，相对场景视口，原点在左上角
+    pub rect: [f32; 4],
+}
+
+impl Default for RenderView {
+    fn default() -> Self {
+        Self {
+            rect: [0.0, 0.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl RenderView {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            rect: [x, y, width, height],
+        }
+    }
+
+    /// 将场景视口均分为 `columns` x `rows` 个视图，按行排列
+    pub fn grid(columns: u32, rows: u32) -> Vec<RenderView> {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let (width, height) = (1.0 / columns as f32, 1.0 / rows as f32);
+
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| {
+                RenderView::new(column as f32 * width, row as f32 * height, width, height)
+            }))
+            .collect()
+    }
+
+    /// 视图在帧缓冲中的视口，边界取整到像素避免相邻视图之间出现缝隙
+    pub fn viewport(&self, scene: &Viewport) -> Viewport {
+        let [x, y, width, height] = self.rect;
+
+        let min = [
+            (scene.offset[0] + x * scene.extent[0]).round(),
+            (scene.offset[1] + y * scene.extent[1]).round(),
+        ];
+        let max = [
+            (scene.offset[0] + (x + width) * scene.extent[0]).round(),
+            (scene.offset[1] + (y + height) * scene.extent[1]).round(),
+        ];
+
+        Viewport {
+            offset: min,
+            extent: [(max[0] - min[0]).max(1.0), (max[1] - min[1]).max(1.0)],
+            depth_range: scene.depth_range.clone(),
+        }
+    }
+}
@@ -1,34 +1,66 @@
 use crate::api::vulkan_helper;
+use crate::asset::asset_server::AssetServer;
+use crate::asset::handle::Handle;
+use crate::asset::hot_reload::HotReload;
+use crate::asset::model::{self, Model};
+use crate::asset::obj;
+use crate::asset::texture::Texture;
 use crate::core::core::new_scope;
 use crate::core::layer_stack::LayerStack;
+use crate::renderer::blend_mode::BlendMode;
 use crate::renderer::frame_commands::FrameCommands;
 use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::readback::Readback;
+use crate::renderer::render_target::{RenderTarget, RenderTargetHandle};
+use crate::renderer::render_view::RenderView;
+use crate::renderer::renderer3d::render_mesh::Renderer3D;
 use crate::renderer::renderer2d::render_helper::get_default_set;
-use crate::renderer::renderer2d::render_image::RenderImage;
 use crate::renderer::shaders::upgrade_shader::{PushConstants, UpgradeShader};
+use crate::renderer::shaders::file_shader::{FileShader, ShaderError};
 use crate::renderer::shaders::Shader;
+use crate::renderer::material::{Material, MaterialHandle, PipelineState};
+use crate::renderer::mesh_buffer::{MeshBuffer, MeshHandle};
+use crate::renderer::shapes::mesh::{InstanceData, Mesh};
 use crate::renderer::shapes::shape_2d::rectangle::Rectangle;
 use crate::renderer::shapes::shape_2d::triangle::Triangle;
 use crate::renderer::shapes::transform::Transform;
 use crate::renderer::shapes::{DrawList, GameObject, Shape};
-use crate::renderer::vertex;
+use crate::renderer::upload_ring::UploadRing;
 use crate::ui::imgui_renderer::ImGuiRenderer;
-use glam::Mat4;
+use glam::{Mat4, Vec2, Vec3};
 use imgui::DrawData;
-use log::error;
+use log::{error, info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use vulkano::buffer::{BufferContents, BufferUsage};
 use vulkano::command_buffer::PrimaryAutoCommandBuffer;
 use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
 use vulkano::descriptor_set::DescriptorSet;
+use vulkano::format::Format;
+use vulkano::image::sampler::{Sampler, SamplerCreateInfo};
+use vulkano::image::view::ImageView;
 use vulkano::memory::allocator::StandardMemoryAllocator;
-use vulkano::pipeline::graphics::viewport::Viewport;
+use vulkano::pipeline::graphics::viewport::{Scissor, Viewport};
 use vulkano::pipeline::{GraphicsPipeline, Pipeline, PipelineBindPoint};
 use vulkano::{
     command_buffer::{allocator::StandardCommandBufferAllocator, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents, SubpassEndInfo},
     device::{Device, Queue},
-    render_pass::{Framebuffer, RenderPass}
+    render_pass::{Framebuffer, RenderPass, Subpass}
 };
-use winit::window::Window;
+
+/// z_index 的有效范围，超出部分会被截断
+pub const Z_INDEX_RANGE: f32 = 10000.0;
+
+/// 图片加载完成前占位矩形的像素尺寸
+pub const IMAGE_PLACEHOLDER_SIZE: Vec2 = Vec2::new(64.0, 64.0);
+
+/// 每帧绘制统计
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RenderStats {
+    pub draw_calls: u32,
+    pub instances: u32,
+}
 
 pub struct Allocators {
     pub buffer_allocator: Arc<StandardMemoryAllocator>,
@@ -37,26 +69,67 @@ pub struct Allocators {
 }
 
 pub struct Renderer {
-    render_image: Box<RenderImage>,
+    /// 纹理等资源，可在 on_ready 中加载并持有句柄
+    pub assets: AssetServer,
+    /// 3D 网格绘制，与 2D 共用相机矩阵
+    pub renderer3d: Renderer3D,
+    /// 通过路径绘制的图片，保持句柄存活以免被回收
+    path_textures: HashMap<String, Handle<Texture>>,
 
     queue: Arc<Queue>,
-    shader: Arc<UpgradeShader>,
+    shader: Arc<dyn Shader>,
+    /// 自定义着色器的文件路径（顶点，片元），首次加载失败时也保留，修复后自动重试
+    shader_paths: Option<(String, String)>,
+    shader_watch: Option<HotReload>,
+    shader_error: Option<String>,
     device: Arc<Device>,
     render_pass: Arc<RenderPass>,
+    /// 场景视口，作为动态状态在绘制前设置
+    viewport: Viewport,
+    /// 之后绘制调用的裁剪矩形，None 表示整个视口
+    scissor: Option<Scissor>,
+    clip_stack: Vec<Option<Scissor>>,
     pipeline: Arc<GraphicsPipeline>,
+    /// 按（材质，管线状态）缓存的管线，材质为 None 时使用内置着色器
+    pipelines: HashMap<(Option<MaterialHandle>, PipelineState), Arc<GraphicsPipeline>>,
+    materials: Vec<Material>,
+    blend_mode: BlendMode,
+    z_index: i32,
+    has_depth: bool,
     pub allocators: Allocators,
 
     draw_list: DrawList,
-    view_proj: [[f32;4];4],
+    meshes: MeshBuffer,
+    upload_ring: UploadRing,
+    triangle_mesh: MeshHandle,
+    rectangle_mesh: MeshHandle,
+    stats: RenderStats,
+    /// 分屏视图，至少有一个
+    views: Vec<RenderView>,
+    /// 每个视图的相机矩阵
+    view_projs: Vec<[[f32;4];4]>,
+    /// 正在执行 on_render 的视图
+    current_view: usize,
+    render_targets: Vec<RenderTarget>,
+    /// begin_target 嵌套的目标，值为 `GameObject::target`
+    target_stack: Vec<u32>,
+    target_sampler: Arc<Sampler>,
     default_set: Arc<DescriptorSet>,
+
+    /// 不经过图层 map 参数提交的纹理上传，下一帧开始时执行
+    pending_uploads: ImageBufferManager,
+
+    screenshot_request: Option<PathBuf>,
+    pending_screenshot: Option<(PathBuf, Readback)>,
 }
 
 impl Renderer {
     pub fn new(
         device: Arc<Device>,
         queue: Arc<Queue>,
-        window: Arc<Window>,
+        extent: [u32; 2],
         render_pass: Arc<RenderPass>,
+        frames_in_flight: usize,
         map: &mut ImageBufferManager
     ) -> Self {
         let command_buffer_allocator =
@@ -68,7 +141,7 @@ impl Renderer {
         let descriptor_set_allocator = vulkan_helper::get_descriptor_set_allocator(device.clone());
 
         // 准备着色器
-        let shader = Arc::new(UpgradeShader::load(device.clone())
+        let shader: Arc<dyn Shader> = Arc::new(UpgradeShader::load(device.clone())
             .unwrap_or_else(|e| {
                 error!("着色器创建失败: {}", e);
                 panic!("着色器创建失败");
@@ -76,7 +149,7 @@ impl Renderer {
 
         let viewport = Viewport {
             offset: [0.0,0.0],
-            extent: window.inner_size().into(),
+            extent: [extent[0] as f32, extent[1] as f32],
             depth_range: 0.0..=1.0
         };
 
@@ -85,9 +158,14 @@ impl Renderer {
             Arc::clone(&device),
             Arc::clone(&render_pass),
             shader.clone(),
-            viewport.clone()
+            PipelineState::default()
         );
 
+        let has_depth = Subpass::from(render_pass.clone(), 0).unwrap().has_depth();
+
+        let mut pipelines = HashMap::new();
+        pipelines.insert((None, PipelineState::default()), pipeline.clone());
+
         // 创建内存分配集
         let allocators = Allocators {
             buffer_allocator,
@@ -103,43 +181,275 @@ impl Renderer {
             map
         );
 
+        // 内置形状只上传一次
+        let mut meshes: MeshBuffer = MeshBuffer::new(allocators.buffer_allocator.clone());
+        let triangle_mesh = meshes.add(Triangle::new().mesh());
+        let rectangle_mesh = meshes.add(Rectangle::new().mesh());
+
+        let renderer3d = Renderer3D::new(
+            device.clone(),
+            render_pass.clone(),
+            default_set.clone(),
+            allocators.buffer_allocator.clone(),
+            frames_in_flight
+        );
+
+        let upload_ring = UploadRing::new(
+            allocators.buffer_allocator.clone(),
+            BufferUsage::VERTEX_BUFFER,
+            frames_in_flight
+        );
+
         Self {
-            render_image: Box::new(RenderImage::new(
+            assets: AssetServer::new(
                 device.clone(),
                 pipeline.layout().set_layouts()[0].clone(),
                 allocators.buffer_allocator.clone(),
                 allocators.descriptor_set_allocator.clone()
-            )),
+            ),
+            renderer3d,
+            path_textures: HashMap::new(),
             queue,
             shader,
+            shader_paths: None,
+            shader_watch: None,
+            shader_error: None,
             device,
             render_pass,
+            viewport,
+            scissor: None,
+            clip_stack: Vec::new(),
             pipeline,
+            pipelines,
+            materials: Vec::new(),
+            blend_mode: BlendMode::default(),
+            z_index: 0,
+            has_depth,
             allocators,
             draw_list: DrawList::default(),
-            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            meshes,
+            upload_ring,
+            triangle_mesh,
+            rectangle_mesh,
+            stats: RenderStats::default(),
+            views: vec![RenderView::default()],
+            view_projs: vec![Mat4::IDENTITY.to_cols_array_2d()],
+            current_view: 0,
+            render_targets: Vec::new(),
+            target_stack: Vec::new(),
+            target_sampler: Sampler::new(device.clone(), SamplerCreateInfo::simple_repeat_linear_no_mipmap())
+                .unwrap_or_else(|e| panic!("创建渲染目标采样器失败: {}", e)),
             default_set,
+            pending_uploads: ImageBufferManager::default(),
+            screenshot_request: None,
+            pending_screenshot: None,
+        }
+    }
+
+    /// 请求在当前帧渲染完成后截图，保存为 PNG
+    pub fn capture_screenshot(&mut self, path: impl Into<PathBuf>) {
+        self.screenshot_request = Some(path.into());
+    }
+
+    /// 当前帧是否录制了截图回读
+    pub fn has_pending_screenshot(&self) -> bool {
+        self.pending_screenshot.is_some()
+    }
+
+    /// 写出已回读的截图，需在该帧 GPU 执行完成后调用
+    pub fn finish_screenshot(&mut self) {
+        let Some((path, readback)) = self.pending_screenshot.take() else {
+            return;
+        };
+
+        let mut image = readback.to_image();
+        // 交换链图像的 alpha 没有意义，截图统一保存为不透明
+        image.pixels_mut().for_each(|pixel| pixel[3] = 255);
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                error!("创建截图目录失败 {}: {}", parent.display(), e);
+                return;
+            }
+        }
+
+        match image.save(&path) {
+            Ok(()) => info!("截图已保存: {}", path.display()),
+            Err(e) => error!("保存截图失败 {}: {}", path.display(), e),
         }
     }
 
+    /// 设置当前视图的相机矩阵，分屏时在每个视图的 on_render 中分别设置；在 begin_target 之后调用时设置渲染目标的相机
     pub fn update_camera(&mut self, view_projection_matrix: Mat4) {
-        self.view_proj = view_projection_matrix.to_cols_array_2d();
+        let view_proj = view_projection_matrix.to_cols_array_2d();
+
+        match self.current_target() {
+            0 => self.view_projs[self.current_view] = view_proj,
+            target => self.render_targets[target as usize - 1].view_proj = view_proj,
+        }
+    }
+
+    /// 设置分屏视图，空列表表示一个铺满场景视口的视图
+    ///
+    /// 每帧会对每个视图执行一次图层栈的 on_render，应在 on_ready 或 on_update 中调用
+    pub fn set_views(&mut self, views: Vec<RenderView>) {
+        self.views = if views.is_empty() {
+            vec![RenderView::default()]
+        } else {
+            views
+        };
+
+        self.view_projs.resize(self.views.len(), Mat4::IDENTITY.to_cols_array_2d());
+    }
+
+    pub fn views(&self) -> &[RenderView] {
+        &self.views
+    }
+
+    /// 正在执行 on_render 的视图序号，图层据此为不同视图选择相机
+    pub fn current_view(&self) -> usize {
+        self.current_view
+    }
+
+    /// 视图在帧缓冲中的视口
+    pub fn view_viewport(&self, view: usize) -> Viewport {
+        self.views[view].viewport(&self.viewport)
+    }
+
+    /// 创建 `width` x `height` 像素的离屏渲染目标
+    pub fn create_render_target(&mut self, width: u32, height: u32) -> RenderTargetHandle {
+        let target = RenderTarget::new(
+            [width, height],
+            Format::R8G8B8A8_UNORM,
+            self.render_pass.clone(),
+            self.pipeline.layout().set_layouts()[0].clone(),
+            self.allocators.buffer_allocator.clone(),
+            self.allocators.descriptor_set_allocator.clone(),
+            self.target_sampler.clone()
+        );
+
+        self.render_targets.push(target);
+        RenderTargetHandle(self.render_targets.len() as u32 - 1)
+    }
+
+    pub fn render_target(&self, handle: RenderTargetHandle) -> &RenderTarget {
+        &self.render_targets[handle.0 as usize]
+    }
+
+    pub fn render_target_mut(&mut self, handle: RenderTargetHandle) -> &mut RenderTarget {
+        &mut self.render_targets[handle.0 as usize]
+    }
+
+    /// 之后的绘制改为画到渲染目标上，需与 `end_target` 成对调用，可以嵌套
+    ///
+    /// 目标只在调用过 begin_target 的帧重新渲染，否则保留上次的内容；
+    /// 分屏时 on_render 对每个视图各执行一次，只需在 `current_view() == 0` 时绘制到目标
+    ///
+    /// 渲染目标按创建顺序渲染，若一个目标中绘制了另一个目标，被绘制的目标需先创建，
+    /// 否则采样到的是上一帧的内容
+    pub fn begin_target(&mut self, handle: RenderTargetHandle) {
+        self.render_targets[handle.0 as usize].used = true;
+
+        self.target_stack.push(handle.0 + 1);
+        self.renderer3d.set_target(handle.0 + 1);
+        self.renderer3d.set_view(0);
+        self.set_scissor(None);
+    }
+
+    /// 恢复绘制到上一层目标
+    pub fn end_target(&mut self) {
+        if self.target_stack.pop().is_none() {
+            warn!("end_target 调用次数多于 begin_target");
+        }
+
+        let target = self.current_target();
+        self.renderer3d.set_target(target);
+        self.renderer3d.set_view(if target == 0 { self.current_view as u32 } else { 0 });
+        self.set_scissor(None);
+    }
+
+    /// 当前绘制到的目标，0 为主帧缓冲
+    fn current_target(&self) -> u32 {
+        self.target_stack.last().copied().unwrap_or(0)
+    }
+
+    /// 当前目标中正在绘制的区域：渲染目标的整个图像，或主帧缓冲中当前视图的视口
+    fn current_viewport(&self) -> Viewport {
+        match self.current_target() {
+            0 => self.view_viewport(self.current_view),
+            target => self.render_targets[target as usize - 1].viewport(),
+        }
+    }
+
+    /// 以渲染目标的像素尺寸绘制它的内容，与 `draw_image` 相同
+    pub fn draw_render_target(&mut self, transform: Transform, handle: RenderTargetHandle) {
+        // 目标正作为颜色附件录制时不能同时被采样
+        if self.target_stack.contains(&(handle.0 + 1)) {
+            warn!("渲染目标 {} 正在 begin_target 中，不能同时被采样，已跳过", handle.0);
+            return;
+        }
+
+        let target = &self.render_targets[handle.0 as usize];
+        let [width, height] = target.extent();
+        let set = target.set.clone();
+
+        // 单位矩形按目标像素尺寸缩放
+        let matrix = transform.to_mat4() * Mat4::from_scale(Vec3::new(width as f32, height as f32, 1.0));
+        self.push_object(self.rectangle_mesh, matrix, [1.0, 1.0, 1.0, 1.0], InstanceData::FULL_UV_RECT, set);
+    }
+
+    /// 切换到下一个视图收集绘制命令，裁剪矩形不跨视图保留
+    fn begin_view(&mut self, view: usize) {
+        self.current_view = view;
+        self.renderer3d.set_view(view as u32);
+        self.set_scissor(None);
+        self.clip_stack.clear();
+    }
+
+    /// 设置之后绘制调用使用的混合模式
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// 获取材质和管线状态对应的管线，不存在时创建并缓存
+    fn get_pipeline(&mut self, material: Option<MaterialHandle>, state: PipelineState) -> Arc<GraphicsPipeline> {
+        let shader = match material {
+            Some(material) => self.materials[material.0 as usize].shader.clone(),
+            None => self.shader.clone(),
+        };
+
+        self.pipelines
+            .entry((material, state))
+            .or_insert_with(|| vulkan_helper::get_graphics_pipeline(
+                self.device.clone(),
+                self.render_pass.clone(),
+                shader,
+                state
+            ))
+            .clone()
     }
 
     fn begin(&mut self, frame: &mut FrameCommands, framebuffer: Arc<Framebuffer>, clear_color: [f32; 4], ) {
+        let mut clear_values = vec![Some(clear_color.into())];
+        if self.has_depth {
+            clear_values.push(Some(1.0f32.into()));
+        }
+
         frame.builder
             .begin_render_pass(
                 RenderPassBeginInfo {
-                    clear_values: vec![Some(clear_color.into())],
+                    clear_values,
                     ..RenderPassBeginInfo::framebuffer(framebuffer)
                 },
                 SubpassBeginInfo {
                     contents: SubpassContents::Inline,
                     ..SubpassBeginInfo::default()
                 }
-            ).unwrap()
-            .bind_pipeline_graphics(self.pipeline.clone())
-            .unwrap();
+            ).unwrap();
     }
 
     fn end(&mut self, frame: &mut FrameCommands) {
@@ -148,107 +458,609 @@ impl Renderer {
             .unwrap();
 
         self.draw_list.clear();
+        self.renderer3d.clear();
+        self.scissor = None;
+        self.clip_stack.clear();
     }
 
-    pub fn draw_triangle(&mut self, transform: Transform, color: [f32; 4]) {
-        let triangle = Triangle::new();
+    /// 设置之后绘制调用的裁剪矩形（帧缓冲像素），None 表示整个视口，2D 和 3D 绘制都生效
+    ///
+    /// 矩形会被限制在当前视图或渲染目标的视口内，分屏时不会画到相邻视图中；每帧结束时重置
+    pub fn set_scissor(&mut self, scissor: Option<Scissor>) {
+        let viewport = vulkan_helper::get_viewport_scissor(&self.current_viewport());
+        let scissor = scissor.map(|scissor| vulkan_helper::get_scissor_intersection(viewport, scissor));
+
+        self.scissor = scissor;
+        self.renderer3d.set_scissor(scissor);
+    }
+
+    pub fn scissor(&self) -> Option<Scissor> {
+        self.scissor
+    }
+
+    /// 将之后的绘制限制在当前裁剪矩形与 `rect` 的交集内，需与 `pop_clip_rect` 成对调用
+    pub fn push_clip_rect(&mut self, rect: Scissor) {
+        let current = self.scissor.unwrap_or_else(|| {
+            vulkan_helper::get_viewport_scissor(&self.current_viewport())
+        });
+
+        self.clip_stack.push(self.scissor);
+        self.set_scissor(Some(vulkan_helper::get_scissor_intersection(current, rect)));
+    }
+
+    /// 恢复上一次 `push_clip_rect` 之前的裁剪矩形
+    pub fn pop_clip_rect(&mut self) {
+        match self.clip_stack.pop() {
+            Some(scissor) => self.set_scissor(scissor),
+            None => warn!("pop_clip_rect 调用次数多于 push_clip_rect"),
+        }
+    }
+
+    /// 设置之后绘制调用的 z_index，值越大越靠前，范围 [-Z_INDEX_RANGE, Z_INDEX_RANGE]
+    ///
+    /// z_index 映射为世界坐标 z ∈ [-1, 1]，2D 相机需满足 near <= -1、far >= 1，
+    /// 否则超出相机深度范围的精灵会被裁掉
+    pub fn set_z_index(&mut self, z_index: i32) {
+        self.z_index = z_index;
+    }
+
+    pub fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    /// 上传一个静态网格，之后可通过返回的句柄实例化绘制
+    pub fn upload_mesh(&mut self, mesh: &Mesh) -> MeshHandle {
+        self.meshes.add(mesh)
+    }
+
+    fn push_object(&mut self, mesh: MeshHandle, matrix: Mat4, color: [f32; 4], uv_rect: [f32; 4], set: Arc<DescriptorSet>) {
+        let obj = self.new_object(mesh, matrix, color, uv_rect, set);
+        self.draw_list.objects.push(new_scope(obj));
+    }
 
-        let mut mesh = triangle.mesh().get_transformed_mesh(&transform);
-        mesh.set_color(color);
+    fn new_object(&self, mesh: MeshHandle, matrix: Mat4, color: [f32; 4], uv_rect: [f32; 4], set: Arc<DescriptorSet>) -> GameObject {
+        // z_index 映射到 z ∈ [-1, 1]，见 set_z_index
+        let mut matrix = matrix;
+        matrix.w_axis.z = (self.z_index as f32 / Z_INDEX_RANGE).clamp(-1.0, 1.0);
+
+        GameObject {
+            mesh,
+            instance: InstanceData {
+                i_transform: matrix.to_cols_array_2d(),
+                i_color: color,
+                i_uv_rect: uv_rect,
+            },
+            set,
+            material: None,
+            material_set: None,
+            state: self.blend_mode.into(),
+            z_index: self.z_index,
+            scissor: self.scissor,
+            // 渲染目标不分屏
+            view: match self.current_target() {
+                0 => self.current_view as u32,
+                _ => 0,
+            },
+            target: self.current_target(),
+        }
+    }
+
+    /// 注册材质，先试建管线以检查着色器是否兼容
+    pub fn create_material(&mut self, material: Material) -> Result<MaterialHandle, ShaderError> {
+        let pipeline = vulkan_helper::try_get_graphics_pipeline(
+            self.device.clone(),
+            self.render_pass.clone(),
+            material.shader.clone(),
+            material.state
+        ).map_err(ShaderError::Vulkan)?;
+
+        let handle = MaterialHandle(self.materials.len() as u32);
+        self.pipelines.insert((Some(handle), material.state), pipeline);
+
+        // 从文件加载的着色器在修改后重新编译
+        if let Some((vs_path, fs_path)) = material.shader.source_paths() {
+            if self.shader_watch.is_none() {
+                self.shader_watch = HotReload::new();
+            }
+
+            if let Some(watch) = self.shader_watch.as_mut() {
+                watch.watch(vs_path);
+                watch.watch(fs_path);
+            }
+        }
+
+        self.materials.push(material);
+
+        Ok(handle)
+    }
+
+    pub fn material(&self, handle: MaterialHandle) -> &Material {
+        &self.materials[handle.0 as usize]
+    }
+
+    /// 修改材质的纹理或颜色；管线状态请使用 `set_material_state`，uniform 请使用 `set_material_uniform`
+    pub fn material_mut(&mut self, handle: MaterialHandle) -> &mut Material {
+        &mut self.materials[handle.0 as usize]
+    }
+
+    /// 修改材质的管线状态，先试建管线，着色器不支持该状态时返回错误且材质保持不变
+    pub fn set_material_state(&mut self, handle: MaterialHandle, state: PipelineState) -> Result<(), ShaderError> {
+        if !self.pipelines.contains_key(&(Some(handle), state)) {
+            let pipeline = vulkan_helper::try_get_graphics_pipeline(
+                self.device.clone(),
+                self.render_pass.clone(),
+                self.materials[handle.0 as usize].shader.clone(),
+                state
+            ).map_err(ShaderError::Vulkan)?;
+
+            self.pipelines.insert((Some(handle), state), pipeline);
+        }
+
+        self.materials[handle.0 as usize].state = state;
+        Ok(())
+    }
+
+    /// 设置材质 set = 1 中 `binding` 处的 uniform 块
+    ///
+    /// 每次设置都会创建新的缓冲和描述符集，已提交的绘制仍使用旧值，因此可以逐次绘制修改
+    pub fn set_material_uniform<U: BufferContents>(&mut self, handle: MaterialHandle, binding: u32, value: U) -> Result<(), ShaderError> {
+        let state = self.materials[handle.0 as usize].state;
+        let pipeline = self.get_pipeline(Some(handle), state);
+
+        let layout = pipeline.layout().set_layouts().get(1).cloned()
+            .ok_or_else(|| ShaderError::Vulkan("材质着色器没有声明 set = 1".to_string()))?;
+
+        let buffer = vulkan_helper::get_uniform_buffer(value, self.allocators.buffer_allocator.clone())
+            .into_bytes();
+
+        let material = &mut self.materials[handle.0 as usize];
+        material.uniforms.retain(|(b, _)| *b != binding);
+        material.uniforms.push((binding, buffer));
+
+        material.set = Some(vulkan_helper::get_uniform_descriptor_set(
+            &material.uniforms,
+            layout,
+            self.allocators.descriptor_set_allocator.clone()
+        ).map_err(ShaderError::Vulkan)?);
+
+        Ok(())
+    }
+
+    /// 用材质绘制已上传的网格，相同网格和材质的绘制合并为一次实例化绘制
+    pub fn draw_mesh(&mut self, mesh: MeshHandle, material: MaterialHandle, transform: Transform) {
+        // 着色器声明了 set = 1 却还没有 uniform 时只能绑定 set = 0，绘制会校验失败
+        let state = self.materials[material.0 as usize].state;
+        if self.materials[material.0 as usize].set.is_none()
+            && self.get_pipeline(Some(material), state).layout().set_layouts().len() > 1
+        {
+            warn!("材质 {} 的着色器需要 set = 1，请先调用 set_material_uniform，已跳过绘制", material.0);
+            return;
+        }
 
-        let obj = GameObject {
-            vertex_len: mesh.vertices.len() as u32,
-            index_count: mesh.indices.len() as u32,
-            transform: transform.to_mat4().to_cols_array_2d(),
-            set: self.default_set.clone(),
+        let mat = &self.materials[material.0 as usize];
+
+        let (uv_rect, set) = match mat.texture.as_ref().and_then(|texture| self.assets.texture(texture)) {
+            Some(texture) => (texture.region.uv_rect, self.assets.page_set(texture)),
+            None => (InstanceData::FULL_UV_RECT, self.default_set.clone()),
         };
 
-        self.draw_list.vertices.extend(mesh.vertices);
-        self.draw_list.indices.extend(mesh.indices);
+        let color = mat.color;
+        let material_set = mat.set.clone();
+
+        let mut obj = self.new_object(mesh, transform.to_mat4(), color, uv_rect, set);
+        obj.material = Some(material);
+        obj.material_set = material_set;
+        obj.state = state;
+
         self.draw_list.objects.push(new_scope(obj));
     }
 
+    pub fn draw_triangle(&mut self, transform: Transform, color: [f32; 4]) {
+        self.push_object(self.triangle_mesh, transform.to_mat4(), color, InstanceData::FULL_UV_RECT, self.default_set.clone());
+    }
+
     pub fn draw_rectangle(&mut self, transform: Transform, color: [f32; 4]) {
-        let rectangle = Rectangle::new();
+        self.push_object(self.rectangle_mesh, transform.to_mat4(), color, InstanceData::FULL_UV_RECT, self.default_set.clone());
+    }
 
-        let mut mesh = rectangle.mesh().get_transformed_mesh(&transform);
-        mesh.set_color(color);
+    /// 按路径绘制图片，首次使用时在后台加载，加载完成前显示默认白色纹理
+    ///
+    /// 通过路径加载的图片不会被回收，需要卸载的图片请使用 `load_texture` 持有句柄
+    pub fn draw_image(&mut self, transform: Transform, image_path: &str) {
+        let handle = match self.path_textures.get(image_path) {
+            Some(handle) => handle.clone(),
+            None => {
+                let handle = self.assets.load_texture(image_path);
+                self.path_textures.insert(image_path.to_string(), handle.clone());
+                handle
+            }
+        };
 
-        let obj = GameObject {
-            vertex_len: mesh.vertices.len() as u32,
-            index_count: mesh.indices.len() as u32,
-            transform: transform.to_mat4().to_cols_array_2d(),
-            set: self.default_set.clone(),
+        self.draw_texture(transform, &handle);
+    }
+
+    pub fn draw_texture(&mut self, transform: Transform, handle: &Handle<Texture>) {
+        let (size, uv_rect, set) = match self.assets.texture(handle) {
+            // 同一图集页上的图片共享描述符集，可以合批
+            Some(texture) => (
+                Vec2::new(texture.width as f32, texture.height as f32),
+                texture.region.uv_rect,
+                self.assets.page_set(texture)
+            ),
+            None => (IMAGE_PLACEHOLDER_SIZE, InstanceData::FULL_UV_RECT, self.default_set.clone()),
         };
 
-        self.draw_list.vertices.extend(mesh.vertices);
-        self.draw_list.indices.extend(mesh.indices);
-        self.draw_list.objects.push(new_scope(obj));
+        // 单位矩形按图片像素尺寸缩放
+        let matrix = transform.to_mat4() * Mat4::from_scale(Vec3::new(size.x, size.y, 1.0));
+        self.push_object(self.rectangle_mesh, matrix, [1.0, 1.0, 1.0, 1.0], uv_rect, set);
     }
 
-    pub fn draw_image(&mut self, transform: Transform, image_path: &str, map: &mut ImageBufferManager) {
-        if let Some(rect) = self.render_image.import_image(image_path, map) {
-            let mesh = rect.mesh().get_transformed_mesh(&transform);
+    /// 导入 glTF（`.gltf` / `.glb`）或 OBJ（`.obj`）模型，网格立即上传，纹理在后台加载
+    pub fn load_model(&mut self, path: &str) -> Result<Model, String> {
+        let is_obj = path.rsplit_once('.')
+            .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case("obj"));
 
-            let obj = GameObject {
-                vertex_len: mesh.vertices.len() as u32,
-                index_count: mesh.indices.len() as u32,
-                transform: transform.to_mat4().to_cols_array_2d(),
-                set: self.render_image.set_sampler(image_path),
-            };
+        let result = if is_obj {
+            obj::load_obj(path, &mut self.renderer3d, &mut self.assets)
+        } else {
+            model::load_gltf(path, &mut self.renderer3d, &mut self.assets)
+        };
+
+        result.inspect_err(|e| error!("{}", e))
+    }
+
+    /// 按节点层级绘制模型，纹理加载完成前只显示基础颜色
+    pub fn draw_model(&mut self, model: &Model, transform: Transform) {
+        for (matrix, mesh) in model.mesh_instances(transform.to_mat4_3d()) {
+            for primitive in &model.meshes[mesh].primitives {
+                let material = primitive.material.and_then(|i| model.materials.get(i));
+                let color = material.map_or([1.0, 1.0, 1.0, 1.0], |material| material.base_color);
 
-            self.draw_list.vertices.extend(mesh.vertices);
-            self.draw_list.indices.extend(mesh.indices);
-            self.draw_list.objects.push(new_scope(obj));
+                let texture = material
+                    .and_then(|material| material.texture.as_ref())
+                    .and_then(|texture| self.assets.texture(texture));
+
+                let (uv_rect, set) = match texture {
+                    Some(texture) => (texture.region.uv_rect, self.assets.page_set(texture)),
+                    None => (InstanceData::FULL_UV_RECT, self.default_set.clone()),
+                };
+
+                self.renderer3d.draw_instance(primitive.mesh, matrix, color, uv_rect, set);
+            }
         }
     }
 
-    pub fn recreate_pipeline(&mut self, viewport: Viewport) {
-        self.pipeline = vulkan_helper::get_graphics_pipeline(
+    /// 开始异步加载纹理，返回的句柄可传给 `draw_texture`，句柄全部丢弃后纹理被回收
+    pub fn load_texture(&mut self, path: &str) -> Handle<Texture> {
+        self.assets.load_texture(path)
+    }
+
+    /// 阻塞直到所有正在加载的资源完成，用于快照测试等需要确定输出的场景
+    pub fn wait_for_assets(&mut self) {
+        self.assets.wait_all(&mut self.pending_uploads);
+    }
+
+    /// 加载时（如 on_ready 中）把目录下的图片预先打包进纹理图集，返回导入数量
+    ///
+    /// 打包的图片可以直接用 `draw_image` 按路径绘制
+    pub fn pack_images(&mut self, dir: &str) -> usize {
+        let handles = self.assets.pack_folder(dir, &mut self.pending_uploads);
+        let count = handles.len();
+        self.path_textures.extend(handles);
+        count
+    }
+
+    /// 上一帧的绘制统计
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// 用文件中的着色器替换内置精灵着色器，文件修改后自动重新编译并重建管线
+    ///
+    /// 着色器需要与内置着色器使用相同的顶点输入、推送常量和描述符布局
+    pub fn load_shader(&mut self, vs_path: &str, fs_path: &str) -> Result<(), ShaderError> {
+        if self.shader_watch.is_none() {
+            self.shader_watch = HotReload::new();
+        }
+
+        if let Some(watch) = self.shader_watch.as_mut() {
+            // 不再监视之前的着色器文件，否则修改旧文件会重新加载当前着色器
+            if let Some((old_vs, old_fs)) = self.shader_paths.take() {
+                for old in [old_vs, old_fs] {
+                    let used_by_material = self.materials.iter()
+                        .filter_map(|material| material.shader.source_paths())
+                        .any(|(vs, fs)| vs == old || fs == old);
+
+                    if !used_by_material {
+                        watch.unwatch(&old);
+                    }
+                }
+            }
+
+            // 加载失败也监视文件，修复后自动生效
+            watch.watch(vs_path);
+            watch.watch(fs_path);
+        }
+
+        self.shader_paths = Some((vs_path.to_string(), fs_path.to_string()));
+        self.reload_shader()
+    }
+
+    /// 最近一次加载自定义着色器的错误，成功后清空
+    pub fn shader_error(&self) -> Option<&str> {
+        self.shader_error.as_deref()
+    }
+
+    /// 先用新着色器试建管线，成功后才替换，避免错误的着色器导致崩溃
+    fn apply_shader(&mut self, shader: FileShader) -> Result<(), ShaderError> {
+        let shader_ref: Arc<dyn Shader> = Arc::new(shader);
+
+        vulkan_helper::try_get_graphics_pipeline(
             self.device.clone(),
             self.render_pass.clone(),
-            self.shader.clone(),
-            viewport
-        )
+            shader_ref.clone(),
+            PipelineState::default()
+        ).map_err(ShaderError::Vulkan)?;
+
+        self.shader = shader_ref;
+        self.rebuild_pipelines();
+
+        Ok(())
     }
 
-    pub fn draw(&mut self, frame: &mut FrameCommands) {
-        let vbo = vertex::get_vbo_2d(self.draw_list.vertices.clone(), self.allocators.buffer_allocator.clone());
-        let ibo = vertex::get_ibo_2d(self.draw_list.indices.clone(), self.allocators.buffer_allocator.clone());
+    /// 着色器文件被修改时重新编译
+    fn check_shader_reload(&mut self) {
+        let Some(watch) = self.shader_watch.as_mut() else {
+            return;
+        };
 
-        frame.builder
-            .bind_vertex_buffers(0, vbo)
-            .unwrap()
-            .bind_index_buffer(ibo)
-            .unwrap();
+        let changed = watch.changed();
+
+        if changed.is_empty() {
+            return;
+        }
+
+        let is_changed = |vs: &str, fs: &str| changed.iter().any(|uri| uri == vs || uri == fs);
 
-        let mut vertex_offset = 0;
-        let mut index_offset = 0;
-
-        for obj in self.draw_list.objects.iter() {
-
-            unsafe {
-                frame.builder
-                    .push_constants(self.pipeline.layout().clone(), 0, 
-                        PushConstants {
-                            view_proj: self.view_proj,
-                            transform: obj.transform,
-                    })
-                    .unwrap()
-                    .bind_descriptor_sets(
-                        PipelineBindPoint::Graphics,
-                        self.pipeline.layout().clone(),
-                        0,
-                        obj.set.clone()
-                    )
-                    .unwrap()
-                    .draw_indexed(obj.index_count, 1, index_offset, vertex_offset, 0)
-                    .unwrap();
+        let shader_changed = self.shader_paths.as_ref()
+            .is_some_and(|(vs, fs)| is_changed(vs.as_str(), fs.as_str()));
+
+        if shader_changed && self.reload_shader().is_ok() {
+            info!("着色器已更新");
+        }
+
+        let materials: Vec<MaterialHandle> = self.materials.iter()
+            .enumerate()
+            .filter(|(_, material)| material.shader.source_paths().is_some_and(|(vs, fs)| is_changed(vs, fs)))
+            .map(|(i, _)| MaterialHandle(i as u32))
+            .collect();
+
+        for handle in materials {
+            match self.reload_material_shader(handle) {
+                Ok(()) => {
+                    info!("材质 {} 的着色器已更新", handle.0);
+                    self.shader_error = None;
+                }
+                Err(e) => {
+                    // 保留旧着色器继续渲染
+                    error!("材质 {}: {}", handle.0, e);
+                    self.shader_error = Some(e.to_string());
+                }
             }
+        }
+    }
+
+    /// 从文件重新加载材质的着色器，试建管线成功后才替换，只丢弃该材质的缓存管线
+    fn reload_material_shader(&mut self, handle: MaterialHandle) -> Result<(), ShaderError> {
+        let material = &self.materials[handle.0 as usize];
+        let Some((vs_path, fs_path)) = material.shader.source_paths() else {
+            return Ok(());
+        };
+
+        let shader: Arc<dyn Shader> = Arc::new(FileShader::load(self.device.clone(), vs_path, fs_path)?);
+
+        let pipeline = vulkan_helper::try_get_graphics_pipeline(
+            self.device.clone(),
+            self.render_pass.clone(),
+            shader.clone(),
+            material.state
+        ).map_err(ShaderError::Vulkan)?;
+
+        // uniform 块布局可能改变，按新布局重建材质的描述符集
+        let set = match pipeline.layout().set_layouts().get(1) {
+            Some(layout) if !material.uniforms.is_empty() => Some(vulkan_helper::get_uniform_descriptor_set(
+                &material.uniforms,
+                layout.clone(),
+                self.allocators.descriptor_set_allocator.clone()
+            ).map_err(ShaderError::Vulkan)?),
+            _ => None,
+        };
+
+        let state = material.state;
+        let material = &mut self.materials[handle.0 as usize];
+        material.shader = shader;
+        material.set = set;
 
-            vertex_offset += obj.vertex_len as i32;
-            index_offset += obj.index_count;
+        self.pipelines.retain(|(material, _), _| *material != Some(handle));
+        self.pipelines.insert((Some(handle), state), pipeline);
+
+        Ok(())
+    }
+
+    /// 从 `shader_paths` 加载并应用自定义着色器，失败时保留当前着色器继续渲染
+    fn reload_shader(&mut self) -> Result<(), ShaderError> {
+        let Some((vs_path, fs_path)) = self.shader_paths.clone() else {
+            return Ok(());
+        };
+
+        info!("加载着色器: {}, {}", vs_path, fs_path);
+
+        let result = FileShader::load(self.device.clone(), &vs_path, &fs_path)
+            .and_then(|shader| self.apply_shader(shader));
+
+        match &result {
+            Ok(()) => self.shader_error = None,
+            Err(e) => {
+                error!("{}", e);
+                self.shader_error = Some(e.to_string());
+            }
+        }
+
+        result
+    }
+
+    /// 更新场景视口，视口是动态状态，不需要重建管线
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// 着色器变化后丢弃缓存的管线，按需重新创建
+    fn rebuild_pipelines(&mut self) {
+        self.pipelines.clear();
+        self.pipeline = self.get_pipeline(None, PipelineState::default());
+    }
+
+    /// 录制绘制到 `target` 的内容，需在该目标的渲染通道内调用
+    ///
+    /// 主帧缓冲（target 为 0）依次录制每个视图：先 3D 网格，再该视图的 2D 内容
+    pub fn draw(&mut self, frame: &mut FrameCommands, target: u32) {
+        self.draw_list.sort(self.has_depth);
+
+        let batches = self.draw_list.batches();
+        let objects = std::mem::take(&mut self.draw_list.objects);
+
+        // 绘制列表按目标排序，只上传该目标的实例
+        let start = objects.partition_point(|obj| obj.target < target);
+        let end = objects.partition_point(|obj| obj.target <= target);
+        let batches: Vec<_> = batches.into_iter()
+            .filter(|batch| (start..end).contains(&(batch.first_instance as usize)))
+            .collect();
+
+        let (vbo, ibo) = self.meshes.buffers();
+        let instance_buffer = (start < end).then(|| self.upload_ring.upload_iter(
+            objects[start..end].iter().map(|obj| obj.instance)
+        ));
+
+        let passes: Vec<(u32, Viewport, [[f32; 4]; 4])> = match target {
+            0 => (0..self.views.len())
+                .map(|view| (view as u32, self.view_viewport(view), self.view_projs[view]))
+                .collect(),
+            target => {
+                let render_target = &self.render_targets[target as usize - 1];
+                vec![(0, render_target.viewport(), render_target.view_proj)]
+            }
+        };
+
+        let mut next_batch = 0;
+
+        for (view, viewport, view_proj) in passes {
+            let viewport_scissor = vulkan_helper::get_viewport_scissor(&viewport);
+
+            frame.builder
+                .set_viewport(0, [viewport].into_iter().collect())
+                .unwrap()
+                .set_scissor(0, [viewport_scissor].into_iter().collect())
+                .unwrap();
+
+            // 3D 网格先于 2D 内容绘制
+            let stats = self.renderer3d.draw(frame, target, view, view_proj, viewport_scissor);
+            self.stats.draw_calls += stats.draw_calls;
+            self.stats.instances += stats.instances;
+
+            // 绘制列表按视图排序，该视图的批次是连续的一段
+            let view_batches = batches[next_batch..].iter()
+                .take_while(|batch| objects[batch.first_instance as usize].view == view)
+                .count();
+            let view_batches = &batches[next_batch..next_batch + view_batches];
+            next_batch += view_batches.len();
+
+            let Some(instance_buffer) = instance_buffer.clone().filter(|_| !view_batches.is_empty()) else {
+                continue;
+            };
+
+            // 3D 绘制绑定了自己的缓冲和管线
+            frame.builder
+                .bind_vertex_buffers(0, (vbo.clone(), instance_buffer))
+                .unwrap()
+                .bind_index_buffer(ibo.clone())
+                .unwrap();
+
+            let mut bound_pipeline = None;
+            let mut bound_sets: Option<(Arc<DescriptorSet>, Option<Arc<DescriptorSet>>)> = None;
+            let mut bound_scissor = Some(viewport_scissor);
+
+            for batch in view_batches.iter() {
+                let obj = &objects[batch.first_instance as usize];
+
+                // 材质或管线状态变化时切换管线
+                if bound_pipeline != Some((obj.material, obj.state)) {
+                    let pipeline = self.get_pipeline(obj.material, obj.state);
+                    frame.builder
+                        .bind_pipeline_graphics(pipeline.clone())
+                        .unwrap()
+                        .push_constants(pipeline.layout().clone(), 0,
+                            PushConstants {
+                                view_proj,
+                            })
+                        .unwrap();
+                    self.pipeline = pipeline;
+                    bound_pipeline = Some((obj.material, obj.state));
+                    bound_sets = None;
+                }
+
+                let sets_changed = !bound_sets.as_ref().is_some_and(|(set, material_set)| {
+                    Arc::ptr_eq(set, &obj.set) && match (material_set, &obj.material_set) {
+                        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                        (None, None) => true,
+                        _ => false,
+                    }
+                });
+
+                if sets_changed {
+                    let mut sets = vec![obj.set.clone()];
+                    sets.extend(obj.material_set.clone());
+
+                    frame.builder
+                        .bind_descriptor_sets(
+                            PipelineBindPoint::Graphics,
+                            self.pipeline.layout().clone(),
+                            0,
+                            sets
+                        )
+                        .unwrap();
+                    bound_sets = Some((obj.set.clone(), obj.material_set.clone()));
+                }
+
+                let scissor = obj.scissor.unwrap_or(viewport_scissor);
+                if bound_scissor != Some(scissor) {
+                    frame.builder
+                        .set_scissor(0, [scissor].into_iter().collect())
+                        .unwrap();
+                    bound_scissor = Some(scissor);
+                }
+
+                unsafe {
+                    frame.builder
+                        .draw_indexed(
+                            obj.mesh.index_count,
+                            batch.instance_count,
+                            obj.mesh.first_index,
+                            obj.mesh.vertex_offset,
+                            batch.first_instance - start as u32
+                        )
+                        .unwrap();
+                }
+
+                self.stats.draw_calls += 1;
+                self.stats.instances += batch.instance_count;
+            }
         }
+
+        self.draw_list.objects = objects;
     }
 
     pub fn render_frame(
@@ -257,28 +1069,79 @@ impl Renderer {
         clear_color: [f32; 4],
         layer_stack: &mut LayerStack,
         map: &mut ImageBufferManager,
-        imgui_renderer: &mut ImGuiRenderer,
-        draw_data: &DrawData,
+        ui: Option<(&mut ImGuiRenderer, &DrawData)>,
         viewport: Viewport
     ) -> Arc<PrimaryAutoCommandBuffer> {
         let mut frame = FrameCommands::new(self.allocators.command_buffer_allocator.clone(), self.queue.clone());
+        self.stats = RenderStats::default();
+        self.upload_ring.next_frame();
+        self.renderer3d.next_frame();
+        self.check_shader_reload();
 
-        self.begin(&mut frame, frame_buffer, clear_color);
-
-        layer_stack.iter_mut().for_each(|layer| {
-            layer.on_render(self, map);
-        });
+        // 回收不再被引用的资源，收取后台解码完成的图片
+        self.assets.maintain();
+        self.assets.poll(&mut self.pending_uploads);
 
-        self.draw(&mut frame);
+        // 先收集绘制命令，保证本帧新导入的纹理在渲染通道开始前上传完毕；每个视图执行一次图层栈
+        for view in 0..self.views.len() {
+            self.begin_view(view);
 
-        imgui_renderer.draw(&mut frame, draw_data, viewport);
+            layer_stack.iter_mut().for_each(|layer| {
+                layer.on_render(self, map);
+            });
+        }
+        self.current_view = 0;
 
-        self.end(&mut frame);
+        if !self.target_stack.is_empty() {
+            warn!("begin_target 调用次数多于 end_target");
+            self.target_stack.clear();
+        }
 
-        // copy_buffer_to_image here!
         map.copy_all_buffer_to_image(&mut frame);
-        // --------------------------
         map.clear();
+        self.pending_uploads.copy_all_buffer_to_image(&mut frame);
+        self.pending_uploads.clear();
+
+        // 先渲染本帧用到的渲染目标，主通道中才能采样到最新内容；
+        // 按创建顺序渲染，先创建的目标可以被后创建的目标采样
+        for index in 0..self.render_targets.len() {
+            if !std::mem::take(&mut self.render_targets[index].used) {
+                continue;
+            }
+
+            let framebuffer = self.render_targets[index].framebuffer.clone();
+            let target_clear_color = self.render_targets[index].clear_color;
+
+            self.begin(&mut frame, framebuffer, target_clear_color);
+            self.draw(&mut frame, index as u32 + 1);
+            frame.builder
+                .end_render_pass(SubpassEndInfo::default())
+                .unwrap();
+        }
+
+        self.begin(&mut frame, frame_buffer.clone(), clear_color);
+
+        self.draw(&mut frame, 0);
+
+        // 无窗口模式下没有 ImGui
+        if let Some((imgui_renderer, draw_data)) = ui {
+            let textures: Vec<Arc<ImageView>> = self.render_targets.iter()
+                .map(|target| target.image_view.clone())
+                .collect();
+
+            imgui_renderer.draw(&mut frame, draw_data, viewport, &textures);
+        }
+
+        self.end(&mut frame);
+
+        if let Some(path) = self.screenshot_request.take() {
+            let readback = Readback::record(
+                &mut frame,
+                frame_buffer.attachments()[0].image().clone(),
+                self.allocators.buffer_allocator.clone()
+            );
+            self.pending_screenshot = Some((path, readback));
+        }
 
         frame.builder.build().unwrap()
     }
@@ -1,2 +1,2 @@
-pub mod render_image;
-pub mod render_helper;
\ No newline at end of file
+pub mod render_helper;
+pub mod texture_atlas;
\ No newline at end of file
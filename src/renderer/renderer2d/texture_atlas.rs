@@ -0,0 +1,353 @@
+use crate::api::vulkan_helper;
+use crate::renderer::image_buffer_man::ImageBufferManager;
+use image::RgbaImage;
+use log::info;
+use std::sync::Arc;
+use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
+use vulkano::descriptor_set::layout::DescriptorSetLayout;
+use vulkano::descriptor_set::{DescriptorSet, WriteDescriptorSet};
+use vulkano::format::Format;
+use vulkano::image::sampler::Sampler;
+use vulkano::image::view::ImageView;
+use vulkano::image::Image;
+use vulkano::memory::allocator::StandardMemoryAllocator;
+
+/// 图集页的默认边长
+pub const ATLAS_PAGE_SIZE: u32 = 2048;
+
+/// 每张图片四周的留边，填充边缘像素以避免线性采样时串色
+const ATLAS_PADDING: u32 = 1;
+
+/// 图片在图集中的位置
+#[derive(Copy, Clone, Debug)]
+pub struct AtlasRegion {
+    /// 所在图集页
+    pub page: usize,
+    /// xy 为 UV 偏移，zw 为 UV 缩放，直接写入实例数据
+    pub uv_rect: [f32; 4],
+    /// 图片左上角在图集页中的像素坐标（不含留边）
+    pub offset: [u32; 2],
+    pub width: u32,
+    pub height: u32,
+}
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    next_x: u32,
+}
+
+/// 按行（货架）摆放矩形的简单装箱器
+pub struct ShelfPacker {
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+    next_y: u32,
+}
+
+impl ShelfPacker {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            shelves: Vec::new(),
+            next_y: 0,
+        }
+    }
+
+    /// 清空所有货架，整页重新可用
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+        self.next_y = 0;
+    }
+
+    /// 放入一个 w x h 的矩形，返回左上角坐标，空间不足时返回 None
+    pub fn pack(&mut self, w: u32, h: u32) -> Option<[u32; 2]> {
+        if w > self.width || h > self.height {
+            return None;
+        }
+
+        // 优先放入高度最接近的已有货架
+        let best = self.shelves.iter_mut()
+            .filter(|shelf| h <= shelf.height && shelf.next_x + w <= self.width)
+            .min_by_key(|shelf| shelf.height - h);
+
+        if let Some(shelf) = best {
+            let pos = [shelf.next_x, shelf.y];
+            shelf.next_x += w;
+            return Some(pos);
+        }
+
+        if self.next_y + h > self.height {
+            return None;
+        }
+
+        let pos = [0, self.next_y];
+        self.shelves.push(Shelf {
+            y: self.next_y,
+            height: h,
+            next_x: w,
+        });
+        self.next_y += h;
+
+        Some(pos)
+    }
+}
+
+struct AtlasPage {
+    image_size: [u32; 2],
+    image: Arc<Image>,
+    set: Arc<DescriptorSet>,
+    packer: ShelfPacker,
+    /// 页上仍在使用的图片数
+    live: usize,
+}
+
+/// 纹理图集：把多张图片打包到少量大纹理中，使不同图片的精灵可以合批绘制
+pub struct TextureAtlas {
+    pages: Vec<AtlasPage>,
+    page_size: u32,
+
+    set_layout: Arc<DescriptorSetLayout>,
+    memory_allocator: Arc<StandardMemoryAllocator>,
+    set_allocator: Arc<StandardDescriptorSetAllocator>,
+    sampler: Arc<Sampler>,
+}
+
+impl TextureAtlas {
+    pub fn new(
+        page_size: u32,
+        set_layout: Arc<DescriptorSetLayout>,
+        memory_allocator: Arc<StandardMemoryAllocator>,
+        set_allocator: Arc<StandardDescriptorSetAllocator>,
+        sampler: Arc<Sampler>,
+    ) -> Self {
+        Self {
+            pages: Vec::new(),
+            page_size,
+            set_layout,
+            memory_allocator,
+            set_allocator,
+            sampler,
+        }
+    }
+
+    /// 将图片放入图集，像素在下一帧开始前随 `map` 上传
+    pub fn insert(&mut self, img: &RgbaImage, map: &mut ImageBufferManager) -> AtlasRegion {
+        let (width, height) = img.dimensions();
+        let (padded_w, padded_h) = padded_size(width, height);
+
+        let placed = self.pages.iter_mut()
+            .enumerate()
+            .find_map(|(i, page)| page.packer.pack(padded_w, padded_h).map(|pos| (i, pos)));
+
+        let (page, pos) = match placed {
+            Some(placed) => placed,
+            None => {
+                // 超过页尺寸的大图单独占一页
+                let size = [self.page_size.max(padded_w), self.page_size.max(padded_h)];
+                self.add_page(size);
+
+                let page = self.pages.len() - 1;
+                let pos = self.pages[page].packer.pack(padded_w, padded_h)
+                    .expect("新图集页放不下图片");
+                (page, pos)
+            }
+        };
+
+        let page_ref = &mut self.pages[page];
+        page_ref.live += 1;
+
+        let staging = vulkan_helper::get_staging(padded_pixels(img), self.memory_allocator.clone());
+        map.add_region(page_ref.image.clone(), staging, pos, [padded_w, padded_h]);
+
+        let [page_w, page_h] = page_ref.image_size;
+        let (page_w, page_h) = (page_w as f32, page_h as f32);
+
+        AtlasRegion {
+            page,
+            uv_rect: [
+                (pos[0] + ATLAS_PADDING) as f32 / page_w,
+                (pos[1] + ATLAS_PADDING) as f32 / page_h,
+                width as f32 / page_w,
+                height as f32 / page_h,
+            ],
+            offset: [pos[0] + ATLAS_PADDING, pos[1] + ATLAS_PADDING],
+            width,
+            height,
+        }
+    }
+
+    /// 原地覆盖区域内的像素，尺寸不一致时返回 false
+    pub fn update(&mut self, region: &AtlasRegion, img: &RgbaImage, map: &mut ImageBufferManager) -> bool {
+        if img.dimensions() != (region.width, region.height) {
+            return false;
+        }
+
+        let Some(page) = self.pages.get(region.page) else {
+            return false;
+        };
+
+        let staging = vulkan_helper::get_staging(padded_pixels(img), self.memory_allocator.clone());
+        map.add_region(
+            page.image.clone(),
+            staging,
+            [region.offset[0] - ATLAS_PADDING, region.offset[1] - ATLAS_PADDING],
+            padded_size(region.width, region.height).into()
+        );
+
+        true
+    }
+
+    /// 释放图片占用的区域
+    ///
+    /// 货架装箱无法回收单个区域，页上的图片全部释放后整页清空复用
+    pub fn remove(&mut self, region: &AtlasRegion) {
+        let Some(page) = self.pages.get_mut(region.page) else {
+            return;
+        };
+
+        page.live = page.live.saturating_sub(1);
+
+        if page.live == 0 {
+            info!("图集页 {} 已空，重新启用", region.page);
+            page.packer.clear();
+        }
+    }
+
+    /// 图集页对应的描述符集
+    pub fn page_set(&self, page: usize) -> Arc<DescriptorSet> {
+        self.pages[page].set.clone()
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn add_page(&mut self, size: [u32; 2]) {
+        info!("创建图集页 {}: {}x{}", self.pages.len(), size[0], size[1]);
+
+        let image = vulkan_helper::get_texture_image_2d(
+            (size[0], size[1]),
+            Format::R8G8B8A8_UNORM,
+            self.memory_allocator.clone()
+        );
+
+        let image_view = ImageView::new_default(image.clone()).unwrap();
+
+        let set = DescriptorSet::new(
+            self.set_allocator.clone(),
+            self.set_layout.clone(),
+            [WriteDescriptorSet::image_view_sampler(0, image_view, self.sampler.clone())],
+            []
+        ).unwrap();
+
+        self.pages.push(AtlasPage {
+            image_size: size,
+            image,
+            set,
+            packer: ShelfPacker::new(size[0], size[1]),
+            live: 0,
+        });
+    }
+}
+
+/// 图片加上四周留边后在图集中占用的尺寸
+fn padded_size(width: u32, height: u32) -> (u32, u32) {
+    (width + ATLAS_PADDING * 2, height + ATLAS_PADDING * 2)
+}
+
+/// 在图片四周复制一圈边缘像素
+fn padded_pixels(img: &RgbaImage) -> Vec<u8> {
+    let (width, height) = img.dimensions();
+    let (padded_w, padded_h) = padded_size(width, height);
+
+    // 空图片没有边缘像素可复制，留边填充透明
+    if width == 0 || height == 0 {
+        return vec![0; padded_w as usize * padded_h as usize * 4];
+    }
+
+    let padded = RgbaImage::from_fn(padded_w, padded_h, |x, y| {
+        let src_x = x.saturating_sub(ATLAS_PADDING).min(width - 1);
+        let src_y = y.saturating_sub(ATLAS_PADDING).min(height - 1);
+        *img.get_pixel(src_x, src_y)
+    });
+
+    padded.into_raw()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shelf_wraps_to_new_row_when_full() {
+        let mut packer = ShelfPacker::new(100, 100);
+
+        assert_eq!(packer.pack(40, 20), Some([0, 0]));
+        assert_eq!(packer.pack(40, 20), Some([40, 0]));
+        // 第一行剩余 20 像素放不下，换到下一行
+        assert_eq!(packer.pack(40, 20), Some([0, 20]));
+        // 较矮的矩形放入剩余宽度足够的已有货架
+        assert_eq!(packer.pack(20, 10), Some([80, 0]));
+    }
+
+    #[test]
+    fn full_page_overflows_into_new_page() {
+        let mut page = ShelfPacker::new(64, 64);
+
+        assert_eq!(page.pack(64, 32), Some([0, 0]));
+        assert_eq!(page.pack(64, 32), Some([0, 32]));
+        assert_eq!(page.pack(1, 1), None);
+
+        // 图集为放不下的图片新建一页，新页从原点开始摆放
+        let mut next_page = ShelfPacker::new(64, 64);
+        assert_eq!(next_page.pack(1, 1), Some([0, 0]));
+
+        // 整页清空后重新可用
+        page.clear();
+        assert_eq!(page.pack(64, 64), Some([0, 0]));
+    }
+
+    #[test]
+    fn padding_counts_toward_page_size() {
+        let size = ATLAS_PAGE_SIZE - ATLAS_PADDING * 2;
+        assert_eq!(padded_size(size, size), (ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE));
+
+        let (w, h) = padded_size(size, size);
+        assert_eq!(ShelfPacker::new(ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE).pack(w, h), Some([0, 0]));
+
+        let (w, h) = padded_size(size + 1, size);
+        assert_eq!(ShelfPacker::new(ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE).pack(w, h), None);
+    }
+
+    #[test]
+    fn rect_larger_than_page_is_rejected() {
+        let mut packer = ShelfPacker::new(64, 64);
+
+        assert_eq!(packer.pack(65, 1), None);
+        assert_eq!(packer.pack(1, 65), None);
+        // 拒绝后不占用空间
+        assert_eq!(packer.pack(64, 64), Some([0, 0]));
+    }
+
+    #[test]
+    fn padded_pixels_repeats_edges() {
+        let img = RgbaImage::from_fn(2, 1, |x, _| image::Rgba([x as u8, 0, 0, 255]));
+        let padded = RgbaImage::from_raw(4, 3, padded_pixels(&img)).unwrap();
+
+        for y in 0..3 {
+            assert_eq!(padded.get_pixel(0, y)[0], 0);
+            assert_eq!(padded.get_pixel(1, y)[0], 0);
+            assert_eq!(padded.get_pixel(2, y)[0], 1);
+            assert_eq!(padded.get_pixel(3, y)[0], 1);
+        }
+    }
+
+    #[test]
+    fn padded_pixels_of_empty_image() {
+        let img = RgbaImage::new(0, 0);
+        assert_eq!(padded_pixels(&img), vec![0; 2 * 2 * 4]);
+    }
+}
@@ -0,0 +1 @@
+pub mod render_mesh;
\ No newline at end of file
@@ -0,0 +1,297 @@
+use crate::api::vulkan_helper;
+use crate::renderer::blend_mode::BlendMode;
+use crate::renderer::frame_commands::FrameCommands;
+use crate::renderer::material::PipelineState;
+use crate::renderer::mesh_buffer::{MeshBuffer, MeshHandle};
+use crate::renderer::renderer::RenderStats;
+use crate::renderer::shaders::mesh_shader::{MeshPushConstants, MeshShader};
+use crate::renderer::shaders::Shader;
+use crate::renderer::shapes::mesh::{InstanceData, Mesh3D, Vertex3D};
+use crate::renderer::shapes::shape_3d::cube::Cube;
+use crate::renderer::shapes::shape_3d::sphere::Sphere;
+use crate::renderer::shapes::transform::Transform;
+use crate::renderer::shapes::Shape3D;
+use crate::renderer::upload_ring::UploadRing;
+use glam::{Mat4, Vec3};
+use log::error;
+use std::sync::Arc;
+use vulkano::buffer::BufferUsage;
+use vulkano::descriptor_set::DescriptorSet;
+use vulkano::device::Device;
+use vulkano::memory::allocator::StandardMemoryAllocator;
+use vulkano::pipeline::graphics::rasterization::CullMode;
+use vulkano::pipeline::graphics::viewport::Scissor;
+use vulkano::pipeline::{GraphicsPipeline, Pipeline, PipelineBindPoint};
+use vulkano::render_pass::RenderPass;
+
+/// 球体默认的经纬分段数
+const SPHERE_SEGMENTS: (u32, u32) = (32, 16);
+
+/// 方向光
+#[derive(Copy, Clone, Debug)]
+pub struct DirectionalLight {
+    /// 从光源指向场景的方向
+    pub direction: Vec3,
+    pub color: Vec3,
+    /// 环境光强度
+    pub ambient: f32,
+}
+
+impl Default for DirectionalLight {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::new(-0.4, -1.0, -0.6),
+            color: Vec3::ONE,
+            ambient: 0.15,
+        }
+    }
+}
+
+struct MeshObject {
+    mesh: MeshHandle,
+    instance: InstanceData,
+    set: Arc<DescriptorSet>,
+    scissor: Option<Scissor>,
+    view: u32,
+    target: u32,
+}
+
+/// 3D 网格渲染器：深度测试、背面剔除、方向光照，相同网格的绘制合并为一次实例化绘制
+///
+/// 与 2D 绘制共用 `Renderer::update_camera` 设置的相机矩阵，在 2D 内容之前绘制
+pub struct Renderer3D {
+    pipeline: Arc<GraphicsPipeline>,
+
+    meshes: MeshBuffer<Vertex3D>,
+    upload_ring: UploadRing,
+    objects: Vec<MeshObject>,
+    default_set: Arc<DescriptorSet>,
+
+    cube_mesh: MeshHandle,
+    sphere_mesh: MeshHandle,
+    /// 之后绘制使用的裁剪矩形，None 表示整个视口
+    scissor: Option<Scissor>,
+    /// 之后绘制所属的分屏视图
+    view: u32,
+    /// 之后绘制到的目标，0 为主帧缓冲
+    target: u32,
+
+    pub light: DirectionalLight,
+}
+
+impl Renderer3D {
+    pub fn new(
+        device: Arc<Device>,
+        render_pass: Arc<RenderPass>,
+        default_set: Arc<DescriptorSet>,
+        allocator: Arc<StandardMemoryAllocator>,
+        frames_in_flight: usize,
+    ) -> Self {
+        let shader: Arc<dyn Shader> = Arc::new(MeshShader::load(device.clone())
+            .unwrap_or_else(|e| {
+                error!("3D着色器创建失败: {}", e);
+                panic!("3D着色器创建失败");
+            }));
+
+        let pipeline = vulkan_helper::get_graphics_pipeline_3d(
+            device,
+            render_pass,
+            shader,
+            Self::pipeline_state()
+        );
+
+        let mut meshes = MeshBuffer::<Vertex3D>::new(allocator.clone());
+        let cube_mesh = meshes.add(Cube::new().mesh());
+        let sphere_mesh = meshes.add(Sphere::new(SPHERE_SEGMENTS.0, SPHERE_SEGMENTS.1).mesh());
+
+        Self {
+            pipeline,
+            meshes,
+            upload_ring: UploadRing::new(allocator, BufferUsage::VERTEX_BUFFER, frames_in_flight),
+            objects: Vec::new(),
+            default_set,
+            cube_mesh,
+            sphere_mesh,
+            scissor: None,
+            view: 0,
+            target: 0,
+            light: DirectionalLight::default(),
+        }
+    }
+
+    /// 不透明、背面剔除、深度读写
+    fn pipeline_state() -> PipelineState {
+        PipelineState {
+            blend_mode: BlendMode::Opaque,
+            cull_mode: CullMode::Back,
+            depth_test: true,
+            depth_write: true,
+        }
+    }
+
+    /// 上传一个静态 3D 网格，之后可通过返回的句柄实例化绘制
+    pub fn upload_mesh(&mut self, mesh: &Mesh3D) -> MeshHandle {
+        self.meshes.add(mesh)
+    }
+
+    pub fn cube_mesh(&self) -> MeshHandle {
+        self.cube_mesh
+    }
+
+    pub fn sphere_mesh(&self) -> MeshHandle {
+        self.sphere_mesh
+    }
+
+    pub fn draw_mesh(&mut self, mesh: MeshHandle, transform: Transform, color: [f32; 4]) {
+        self.draw_instance(mesh, transform.to_mat4_3d(), color, InstanceData::FULL_UV_RECT, self.default_set.clone());
+    }
+
+    /// 以任意矩阵和纹理绘制网格，`set` 为纹理所在图集页的描述符集
+    pub fn draw_instance(&mut self, mesh: MeshHandle, matrix: Mat4, color: [f32; 4], uv_rect: [f32; 4], set: Arc<DescriptorSet>) {
+        self.objects.push(MeshObject {
+            mesh,
+            instance: InstanceData {
+                i_transform: matrix.to_cols_array_2d(),
+                i_color: color,
+                i_uv_rect: uv_rect,
+            },
+            set,
+            scissor: self.scissor,
+            view: self.view,
+            target: self.target,
+        });
+    }
+
+    pub fn draw_cube(&mut self, transform: Transform, color: [f32; 4]) {
+        self.draw_mesh(self.cube_mesh, transform, color);
+    }
+
+    pub fn draw_sphere(&mut self, transform: Transform, color: [f32; 4]) {
+        self.draw_mesh(self.sphere_mesh, transform, color);
+    }
+
+    pub fn next_frame(&mut self) {
+        self.upload_ring.next_frame();
+    }
+
+    /// 设置之后绘制使用的裁剪矩形（帧缓冲像素），None 表示整个视口
+    ///
+    /// 不做视口限制，应通过 `Renderer::set_scissor` 设置
+    pub fn set_scissor(&mut self, scissor: Option<Scissor>) {
+        self.scissor = scissor;
+    }
+
+    /// 设置之后绘制所属的分屏视图，由 `Renderer` 在每个视图的 on_render 之前调用
+    pub fn set_view(&mut self, view: u32) {
+        self.view = view;
+    }
+
+    /// 设置之后绘制到的目标，由 `Renderer::begin_target` / `end_target` 调用
+    pub fn set_target(&mut self, target: u32) {
+        self.target = target;
+    }
+
+    pub fn clear(&mut self) {
+        self.objects.clear();
+        self.scissor = None;
+        self.view = 0;
+        self.target = 0;
+    }
+
+    /// 录制本帧提交到 `target` 的 `view` 的 3D 网格，需在渲染通道内、设置好视口之后调用
+    ///
+    /// 未设置裁剪矩形的网格使用 `default_scissor`
+    pub fn draw(&mut self, frame: &mut FrameCommands, target: u32, view: u32, view_proj: [[f32; 4]; 4], default_scissor: Scissor) -> RenderStats {
+        let mut stats = RenderStats::default();
+
+        // 同一目标和视图、相同裁剪矩形、网格和纹理相邻，便于合批
+        self.objects.sort_by_key(|obj| (
+            obj.target,
+            obj.view,
+            obj.scissor.map(|scissor| (scissor.offset, scissor.extent)),
+            obj.mesh.first_index,
+            obj.mesh.vertex_offset,
+            Arc::as_ptr(&obj.set) as usize
+        ));
+
+        let start = self.objects.partition_point(|obj| (obj.target, obj.view) < (target, view));
+        let end = self.objects.partition_point(|obj| (obj.target, obj.view) <= (target, view));
+        let objects = &self.objects[start..end];
+
+        if objects.is_empty() {
+            return stats;
+        }
+
+        let (vbo, ibo) = self.meshes.buffers();
+        let instance_buffer = self.upload_ring.upload_iter(
+            objects.iter().map(|obj| obj.instance)
+        );
+
+        let light_dir = self.light.direction.normalize_or(Vec3::NEG_Y);
+
+        frame.builder
+            .bind_pipeline_graphics(self.pipeline.clone())
+            .unwrap()
+            .push_constants(self.pipeline.layout().clone(), 0,
+                MeshPushConstants {
+                    view_proj,
+                    light_dir: light_dir.extend(0.0).to_array(),
+                    light_color: self.light.color.extend(self.light.ambient).to_array(),
+                })
+            .unwrap()
+            .bind_vertex_buffers(0, (vbo, instance_buffer))
+            .unwrap()
+            .bind_index_buffer(ibo)
+            .unwrap();
+
+        let mut bound_set: Option<Arc<DescriptorSet>> = None;
+        let mut bound_scissor = None;
+        let mut first = 0;
+
+        while first < objects.len() {
+            let obj = &objects[first];
+
+            let count = objects[first..].iter()
+                .take_while(|other| other.mesh == obj.mesh && other.scissor == obj.scissor && Arc::ptr_eq(&other.set, &obj.set))
+                .count();
+
+            let scissor = obj.scissor.unwrap_or(default_scissor);
+            if bound_scissor != Some(scissor) {
+                frame.builder
+                    .set_scissor(0, [scissor].into_iter().collect())
+                    .unwrap();
+                bound_scissor = Some(scissor);
+            }
+
+            if !bound_set.as_ref().is_some_and(|set| Arc::ptr_eq(set, &obj.set)) {
+                frame.builder
+                    .bind_descriptor_sets(
+                        PipelineBindPoint::Graphics,
+                        self.pipeline.layout().clone(),
+                        0,
+                        obj.set.clone()
+                    )
+                    .unwrap();
+                bound_set = Some(obj.set.clone());
+            }
+
+            unsafe {
+                frame.builder
+                    .draw_indexed(
+                        obj.mesh.index_count,
+                        count as u32,
+                        obj.mesh.first_index,
+                        obj.mesh.vertex_offset,
+                        first as u32
+                    )
+                    .unwrap();
+            }
+
+            stats.draw_calls += 1;
+            stats.instances += count as u32;
+            first += count;
+        }
+
+        stats
+    }
+}
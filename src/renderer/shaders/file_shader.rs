@@ -0,0 +1,110 @@
+use crate::asset::vfs;
+use crate::renderer::shaders::Shader;
+use shaderc::{CompileOptions, Compiler, ShaderKind};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use vulkano::device::Device;
+use vulkano::shader::spirv::bytes_to_words;
+use vulkano::shader::{ShaderModule, ShaderModuleCreateInfo};
+
+/// 运行时加载着色器的错误，编译错误信息原样保留以便显示
+#[derive(Clone, Debug)]
+pub enum ShaderError {
+    /// 读取文件失败
+    Io(String),
+    /// GLSL 编译失败
+    Compile(String),
+    /// SPIR-V 无效或创建管线失败
+    Vulkan(String),
+}
+
+impl Display for ShaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderError::Io(e) => write!(f, "读取着色器失败: {e}"),
+            ShaderError::Compile(e) => write!(f, "编译着色器失败: {e}"),
+            ShaderError::Vulkan(e) => write!(f, "创建着色器失败: {e}"),
+        }
+    }
+}
+
+/// 从文件加载的着色器，支持 GLSL 源码（`.vert` / `.frag` / `.glsl`）和预编译的 `.spv`
+///
+/// 路径经过虚拟文件系统解析，可以使用 `assets://shaders/sprite.frag`
+#[derive(Clone, Debug)]
+pub struct FileShader {
+    pub vs: Arc<ShaderModule>,
+    pub fs: Arc<ShaderModule>,
+    vs_path: String,
+    fs_path: String,
+}
+
+impl Shader for FileShader {
+    fn fs(&self) -> &Arc<ShaderModule> {
+        &self.fs
+    }
+
+    fn vs(&self) -> &Arc<ShaderModule> {
+        &self.vs
+    }
+
+    fn source_paths(&self) -> Option<(&str, &str)> {
+        Some((&self.vs_path, &self.fs_path))
+    }
+}
+
+impl FileShader {
+    pub fn load(device: Arc<Device>, vs_path: &str, fs_path: &str) -> Result<Self, ShaderError> {
+        Ok(Self {
+            vs: load_module(device.clone(), vs_path, ShaderKind::Vertex)?,
+            fs: load_module(device, fs_path, ShaderKind::Fragment)?,
+            vs_path: vs_path.to_string(),
+            fs_path: fs_path.to_string(),
+        })
+    }
+
+    /// 从相同的文件重新加载，失败时原着色器不受影响
+    pub fn reload(&self, device: Arc<Device>) -> Result<Self, ShaderError> {
+        Self::load(device, &self.vs_path, &self.fs_path)
+    }
+
+    pub fn vs_path(&self) -> &str {
+        &self.vs_path
+    }
+
+    pub fn fs_path(&self) -> &str {
+        &self.fs_path
+    }
+}
+
+fn load_module(device: Arc<Device>, path: &str, kind: ShaderKind) -> Result<Arc<ShaderModule>, ShaderError> {
+    let bytes = vfs::read(path).map_err(|e| ShaderError::Io(format!("{path}: {e}")))?;
+
+    let words = if path.ends_with(".spv") {
+        bytes_to_words(&bytes)
+            .map_err(|e| ShaderError::Vulkan(format!("{path}: {e}")))?
+            .into_owned()
+    } else {
+        compile_glsl(&bytes, path, kind)?
+    };
+
+    // SPIR-V 来自 shaderc 编译结果或用户提供的文件，vulkano 会在创建时做反射校验
+    unsafe { ShaderModule::new(device, ShaderModuleCreateInfo::new(&words)) }
+        .map_err(|e| ShaderError::Vulkan(format!("{path}: {e}")))
+}
+
+fn compile_glsl(bytes: &[u8], path: &str, kind: ShaderKind) -> Result<Vec<u32>, ShaderError> {
+    let source = std::str::from_utf8(bytes)
+        .map_err(|e| ShaderError::Io(format!("{path}: {e}")))?;
+
+    let compiler = Compiler::new()
+        .ok_or_else(|| ShaderError::Compile("无法创建 shaderc 编译器".to_string()))?;
+    let options = CompileOptions::new()
+        .ok_or_else(|| ShaderError::Compile("无法创建 shaderc 编译选项".to_string()))?;
+
+    let artifact = compiler
+        .compile_into_spirv(source, kind, path, "main", Some(&options))
+        .map_err(|e| ShaderError::Compile(e.to_string()))?;
+
+    Ok(artifact.as_binary().to_vec())
+}
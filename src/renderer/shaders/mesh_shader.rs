@@ -0,0 +1,112 @@
+use crate::renderer::shaders::Shader;
+use std::sync::Arc;
+use vulkano::buffer::BufferContents;
+use vulkano::device::Device;
+use vulkano::shader::ShaderModule;
+use vulkano::{Validated, VulkanError};
+
+mod vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        src: r"
+            #version 460
+
+            layout(location = 0) in vec3 position;
+            layout(location = 1) in vec3 normal;
+            layout(location = 2) in vec2 uv;
+            layout(location = 3) in vec4 color;
+
+            // 每实例数据
+            layout(location = 4) in mat4 i_transform;
+            layout(location = 8) in vec4 i_color;
+            layout(location = 9) in vec4 i_uv_rect;
+
+            layout(push_constant) uniform PushConstants {
+                mat4 view_proj;
+                vec4 light_dir;
+                vec4 light_color;
+            } pc;
+
+            layout(location = 0) out vec3 v_normal;
+            layout(location = 1) out vec2 v_uv;
+            layout(location = 2) out vec4 v_color;
+
+            void main() {
+                // 法线矩阵，非均匀缩放时保持法线垂直于表面
+                v_normal = transpose(inverse(mat3(i_transform))) * normal;
+                v_uv = i_uv_rect.xy + uv * i_uv_rect.zw;
+                v_color = color * i_color;
+                gl_Position = pc.view_proj * i_transform * vec4(position, 1.0);
+            }
+        "
+    }
+}
+
+mod fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        src: r"
+            #version 460
+
+            layout(location = 0) in vec3 v_normal;
+            layout(location = 1) in vec2 v_uv;
+            layout(location = 2) in vec4 v_color;
+
+            layout(set = 0, binding = 0) uniform sampler2D tex;
+
+            layout(push_constant) uniform PushConstants {
+                mat4 view_proj;
+                vec4 light_dir;
+                vec4 light_color;
+            } pc;
+
+            layout(location = 0) out vec4 f_color;
+
+            void main() {
+                vec3 n = normalize(v_normal);
+                float diffuse = max(dot(n, -normalize(pc.light_dir.xyz)), 0.0);
+                // light_color.w 为环境光强度
+                vec3 light = pc.light_color.rgb * diffuse + vec3(pc.light_color.w);
+
+                vec4 base = texture(tex, v_uv) * v_color;
+                f_color = vec4(base.rgb * light, base.a);
+            }
+        "
+    }
+}
+
+/// 带方向光的 3D 网格着色器
+#[derive(Clone, Debug)]
+pub struct MeshShader {
+    pub vs: Arc<ShaderModule>,
+    pub fs: Arc<ShaderModule>,
+}
+
+impl Shader for MeshShader {
+    fn fs(&self) -> &Arc<ShaderModule> {
+        &self.fs
+    }
+
+    fn vs(&self) -> &Arc<ShaderModule> {
+        &self.vs
+    }
+}
+
+impl MeshShader {
+    pub fn load(device: Arc<Device>) -> Result<Self, Validated<VulkanError>> {
+        Ok(Self {
+            vs: vs::load(device.clone())?,
+            fs: fs::load(device.clone())?,
+        })
+    }
+}
+
+#[repr(C)]
+#[derive(BufferContents, Copy, Clone)]
+pub struct MeshPushConstants {
+    pub view_proj: [[f32;4];4],
+    /// xyz 为光照方向（从光源指向场景）
+    pub light_dir: [f32;4],
+    /// rgb 为光源颜色，w 为环境光强度
+    pub light_color: [f32;4],
+}
@@ -1,13 +1,16 @@
-use std::fmt::Debug;
 use std::sync::Arc;
-use vulkano::device::Device;
 use vulkano::shader::ShaderModule;
-use vulkano::{Validated, VulkanError};
 pub mod upgrade_shader;
+pub mod file_shader;
+pub mod mesh_shader;
 
 pub trait Shader {
 
     fn fs(&self) -> &Arc<ShaderModule>;
     fn vs(&self) -> &Arc<ShaderModule>;
-    fn load(device: Arc<Device>) -> Result<Self, Validated<VulkanError>> where Self: Sized + Clone + Debug;
+
+    /// 着色器的源文件（顶点，片元），从文件加载的着色器在文件修改后会被重新加载
+    fn source_paths(&self) -> Option<(&str, &str)> {
+        None
+    }
 }
\ No newline at end of file
@@ -1,5 +1,4 @@
 use crate::renderer::shaders::Shader;
-use std::fmt::Debug;
 use std::sync::Arc;
 use vulkano::buffer::BufferContents;
 use vulkano::device::Device;
@@ -16,18 +15,22 @@ mod vs {
             layout(location = 1) in vec2 uv;
             layout(location = 2) in vec4 color;
 
+            // 每实例数据
+            layout(location = 3) in mat4 i_transform;
+            layout(location = 7) in vec4 i_color;
+            layout(location = 8) in vec4 i_uv_rect;
+
             layout(push_constant) uniform PushConstants {
                 mat4 view_proj;
-                mat4 transform;
             } pc;
 
             layout(location = 0) out vec2 v_uv;
             layout(location = 1) out vec4 v_color;
 
             void main() {
-                v_uv = uv;
-                v_color = color;
-                gl_Position = pc.view_proj * pc.transform * position;
+                v_uv = i_uv_rect.xy + uv * i_uv_rect.zw;
+                v_color = color * i_color;
+                gl_Position = pc.view_proj * i_transform * position;
             }
         "
     }
@@ -67,11 +70,10 @@ impl Shader for UpgradeShader {
     fn vs(&self) -> &Arc<ShaderModule> {
         &self.vs
     }
+}
 
-    fn load(device: Arc<Device>) -> Result<Self, Validated<VulkanError>>
-    where
-        Self: Sized + Clone + Debug
-    {
+impl UpgradeShader {
+    pub fn load(device: Arc<Device>) -> Result<Self, Validated<VulkanError>> {
         Ok(Self {
             vs: vs::load(device.clone())?,
             fs: fs::load(device.clone())?,
@@ -83,5 +85,4 @@ impl Shader for UpgradeShader {
 #[derive(BufferContents, Copy, Clone)]
 pub struct PushConstants {
     pub view_proj: [[f32;4];4],
-    pub transform: [[f32;4];4],
 }
\ No newline at end of file
@@ -14,6 +14,36 @@ pub struct AzerVertex {
     pub color: [f32; 4]
 }
 
+/// 3D 网格顶点，法线用于光照
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Debug)]
+pub struct Vertex3D {
+    #[format(R32G32B32_SFLOAT)]
+    pub position: [f32; 3],
+    #[format(R32G32B32_SFLOAT)]
+    pub normal: [f32; 3],
+    #[format(R32G32_SFLOAT)]
+    pub uv: [f32; 2],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4]
+}
+
+/// 每实例数据，作为第二个顶点缓冲按实例步进
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy, Debug)]
+pub struct InstanceData {
+    #[format(R32G32B32A32_SFLOAT)]
+    pub i_transform: [[f32; 4]; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub i_color: [f32; 4],
+    /// 纹理坐标区域：xy 为偏移，zw 为缩放
+    #[format(R32G32B32A32_SFLOAT)]
+    pub i_uv_rect: [f32; 4],
+}
+
+impl InstanceData {
+    pub const FULL_UV_RECT: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+}
 
 #[derive(Debug, Clone)]
 pub struct Mesh {
@@ -42,4 +72,11 @@ impl Mesh {
             vertex.color = color
         })
     }
-}
\ No newline at end of file
+}
+
+/// 3D 网格，三角形正面为逆时针
+#[derive(Debug, Clone)]
+pub struct Mesh3D {
+    pub vertices: Vec<Vertex3D>,
+    pub indices: Vec<u32>,
+}
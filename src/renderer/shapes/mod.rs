@@ -1,36 +1,90 @@
 use std::sync::Arc;
 
 use vulkano::descriptor_set::DescriptorSet;
+use vulkano::pipeline::graphics::viewport::Scissor;
 
 use crate::core::core::Scope;
-use crate::renderer::shapes::mesh::{AzerVertex, Mesh};
+use crate::renderer::material::{MaterialHandle, PipelineState};
+use crate::renderer::mesh_buffer::MeshHandle;
+use crate::renderer::shapes::mesh::{InstanceData, Mesh, Mesh3D};
 
 pub mod transform;
 pub mod shape_2d;
+pub mod shape_3d;
 pub mod mesh;
 
 pub trait Shape {
     fn mesh(&self) -> &Mesh;
 }
 
+pub trait Shape3D {
+    fn mesh(&self) -> &Mesh3D;
+}
+
 pub struct GameObject {
-    pub vertex_len: u32,
-    pub index_count: u32,
-    pub transform: [[f32; 4]; 4],
+    pub mesh: MeshHandle,
+    pub instance: InstanceData,
     pub set: Arc<DescriptorSet>,
+    /// None 表示使用内置精灵着色器
+    pub material: Option<MaterialHandle>,
+    /// 材质的 uniform 描述符集（set = 1），记录绘制时的值
+    pub material_set: Option<Arc<DescriptorSet>>,
+    pub state: PipelineState,
+    pub z_index: i32,
+    /// 裁剪矩形，None 表示整个视口
+    pub scissor: Option<Scissor>,
+    /// 所属的分屏视图
+    pub view: u32,
+    /// 绘制到的目标，0 为主帧缓冲，n 为第 n - 1 个渲染目标
+    pub target: u32,
+}
+
+/// 决定对象能否合批的字段，描述符集按指针比较
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BatchKey {
+    pub mesh: MeshHandle,
+    pub target: u32,
+    pub view: u32,
+    pub scissor: Option<Scissor>,
+    pub material: Option<MaterialHandle>,
+    pub state: PipelineState,
+    pub set: *const DescriptorSet,
+    pub material_set: Option<*const DescriptorSet>,
+}
+
+impl GameObject {
+    pub fn batch_key(&self) -> BatchKey {
+        BatchKey {
+            mesh: self.mesh,
+            target: self.target,
+            view: self.view,
+            scissor: self.scissor,
+            material: self.material,
+            state: self.state,
+            set: Arc::as_ptr(&self.set),
+            material_set: self.material_set.as_ref().map(Arc::as_ptr),
+        }
+    }
+
+    /// 网格、纹理、材质、管线状态和裁剪矩形都相同的对象可以合并为一次实例化绘制
+    pub fn can_batch_with(&self, other: &GameObject) -> bool {
+        self.batch_key() == other.batch_key()
+    }
+}
+
+/// 一次实例化绘制：连续的 `instance_count` 个对象
+pub struct DrawBatch {
+    pub first_instance: u32,
+    pub instance_count: u32,
 }
 
 pub struct DrawList {
-    pub vertices: Vec<AzerVertex>,
-    pub indices: Vec<u32>,
     pub objects: Vec<Scope<GameObject>>,
 }
 
 impl Default for DrawList {
     fn default() -> Self {
         Self {
-            vertices: Vec::new(),
-            indices: Vec::new(),
             objects: Vec::new(),
         }
     }
@@ -38,8 +92,200 @@ impl Default for DrawList {
 
 impl DrawList {
     pub fn clear(&mut self) {
-        self.vertices.clear();
-        self.indices.clear();
         self.objects.clear();
     }
+
+    /// 将相邻且可合并的对象划分为批次，需在 `sort` 之后调用
+    pub fn batches(&self) -> Vec<DrawBatch> {
+        batch_runs(self.objects.iter().map(|obj| obj.batch_key()))
+    }
+
+    /// 先按渲染目标和视图分组，再按 z_index 排序
+    ///
+    /// 有深度缓冲时：不透明物体在前且从前到后，半透明物体在后且从后到前；
+    /// 无深度缓冲时全部从后到前（画家算法）。
+    /// 相同 z_index 的半透明物体保持提交顺序；不透明物体由深度测试决定遮挡，按批次键聚合以减少 Draw Call，
+    /// 它们之间互相重叠时的先后不保证，需要确定的覆盖顺序请使用不同的 z_index
+    pub fn sort(&mut self, has_depth: bool) {
+        self.objects.sort_by_key(|obj| draw_order(&obj.batch_key(), obj.z_index, has_depth));
+    }
+}
+
+/// 把连续且键相同的对象划分为批次
+fn batch_runs(keys: impl Iterator<Item = BatchKey>) -> Vec<DrawBatch> {
+    let mut batches: Vec<DrawBatch> = Vec::new();
+    let mut last_key = None;
+
+    for (i, key) in keys.enumerate() {
+        match batches.last_mut() {
+            Some(last) if last_key == Some(key) => last.instance_count += 1,
+            _ => {
+                batches.push(DrawBatch {
+                    first_instance: i as u32,
+                    instance_count: 1,
+                });
+                last_key = Some(key);
+            }
+        }
+    }
+
+    batches
+}
+
+/// 可排序的批次键：材质、纹理、材质 uniform、网格和裁剪矩形，描述符集按指针比较
+///
+/// 管线状态不参与比较，材质对象的状态由材质决定，不透明的精灵状态都相同
+type BatchOrder = (Option<u32>, usize, usize, (u32, i32, u32), Option<([u32; 2], [u32; 2])>);
+
+/// `DrawList::sort` 的排序键，只有深度缓冲下的不透明物体在相同 z_index 内按批次键排序
+fn draw_order(key: &BatchKey, z_index: i32, has_depth: bool) -> ((u32, u32, u8, i64), Option<BatchOrder>) {
+    let front_to_back = has_depth && !key.state.blend_mode.is_transparent();
+
+    let batch = front_to_back.then(|| (
+        key.material.map(|material| material.0),
+        key.set as usize,
+        key.material_set.map_or(0, |set| set as usize),
+        (key.mesh.first_index, key.mesh.vertex_offset, key.mesh.index_count),
+        key.scissor.map(|scissor| (scissor.offset, scissor.extent)),
+    ));
+
+    (sort_key(key.target, key.view, z_index, front_to_back), batch)
+}
+
+/// 绘制顺序，`front_to_back` 的对象排在同组其他对象之前
+fn sort_key(target: u32, view: u32, z_index: i32, front_to_back: bool) -> (u32, u32, u8, i64) {
+    if front_to_back {
+        (target, view, 0, -(z_index as i64))
+    } else {
+        (target, view, 1, z_index as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::blend_mode::BlendMode;
+
+    fn key() -> BatchKey {
+        BatchKey {
+            mesh: MeshHandle {
+                first_index: 0,
+                vertex_offset: 0,
+                index_count: 6,
+            },
+            target: 0,
+            view: 0,
+            scissor: None,
+            material: None,
+            state: PipelineState::default(),
+            set: std::ptr::null(),
+            material_set: None,
+        }
+    }
+
+    fn counts(batches: &[DrawBatch]) -> Vec<(u32, u32)> {
+        batches.iter().map(|batch| (batch.first_instance, batch.instance_count)).collect()
+    }
+
+    #[test]
+    fn identical_adjacent_objects_merge() {
+        let batches = batch_runs([key(), key(), key()].into_iter());
+        assert_eq!(counts(&batches), [(0, 3)]);
+    }
+
+    #[test]
+    fn differing_scissor_splits_batch() {
+        let clipped = BatchKey {
+            scissor: Some(Scissor {
+                offset: [0, 0],
+                extent: [16, 16],
+            }),
+            ..key()
+        };
+
+        let batches = batch_runs([key(), clipped, clipped, key()].into_iter());
+        assert_eq!(counts(&batches), [(0, 1), (1, 2), (3, 1)]);
+    }
+
+    #[test]
+    fn differing_view_or_target_splits_batch() {
+        let other_view = BatchKey { view: 1, ..key() };
+        let other_target = BatchKey { target: 1, ..key() };
+
+        let batches = batch_runs([key(), other_view, other_target, other_target].into_iter());
+        assert_eq!(counts(&batches), [(0, 1), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn differing_state_or_set_splits_batch() {
+        let additive = BatchKey { state: BlendMode::Additive.into(), ..key() };
+        let other_set = BatchKey { set: std::ptr::dangling(), ..key() };
+
+        let batches = batch_runs([key(), additive, other_set].into_iter());
+        assert_eq!(counts(&batches), [(0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn sort_groups_by_target_then_view() {
+        let mut keys = [
+            sort_key(1, 0, 5, false),
+            sort_key(0, 1, 0, false),
+            sort_key(0, 0, 9, false),
+            sort_key(0, 0, -9, false),
+        ];
+        keys.sort();
+
+        assert_eq!(keys, [
+            sort_key(0, 0, -9, false),
+            sort_key(0, 0, 9, false),
+            sort_key(0, 1, 0, false),
+            sort_key(1, 0, 5, false),
+        ]);
+
+        // 不透明物体从前到后，排在半透明物体之前
+        assert!(sort_key(0, 0, 9, true) < sort_key(0, 0, -9, true));
+        assert!(sort_key(0, 0, -9, true) < sort_key(0, 0, -10, false));
+    }
+
+    /// 按 `DrawList::sort` 的规则排序后划分批次
+    fn sorted_batches(mut objects: Vec<(BatchKey, i32)>, has_depth: bool) -> Vec<(u32, u32)> {
+        objects.sort_by_key(|(key, z_index)| draw_order(key, *z_index, has_depth));
+        counts(&batch_runs(objects.into_iter().map(|(key, _)| key)))
+    }
+
+    fn interleaved_shapes(blend_mode: BlendMode) -> Vec<(BatchKey, i32)> {
+        let rectangle = BatchKey { state: blend_mode.into(), ..key() };
+        let triangle = BatchKey {
+            mesh: MeshHandle {
+                first_index: 6,
+                vertex_offset: 4,
+                index_count: 3,
+            },
+            ..rectangle
+        };
+
+        (0..6).map(|i| (if i % 2 == 0 { triangle } else { rectangle }, 0)).collect()
+    }
+
+    #[test]
+    fn interleaved_opaque_shapes_merge_into_two_batches() {
+        assert_eq!(sorted_batches(interleaved_shapes(BlendMode::Opaque), true), [(0, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn interleaved_shapes_keep_order_without_depth_or_when_transparent() {
+        let alternating: Vec<(u32, u32)> = (0..6).map(|i| (i, 1)).collect();
+        assert_eq!(sorted_batches(interleaved_shapes(BlendMode::Opaque), false), alternating);
+        assert_eq!(sorted_batches(interleaved_shapes(BlendMode::Alpha), true), alternating);
+    }
+
+    #[test]
+    fn batch_order_does_not_cross_z_index() {
+        let mut objects = interleaved_shapes(BlendMode::Opaque);
+        objects[0].1 = 1;
+        objects[1].1 = 1;
+
+        // z_index 1 的三角形和矩形在前，其余四个对象合并为两批
+        assert_eq!(sorted_batches(objects, true), [(0, 1), (1, 1), (2, 2), (4, 2)]);
+    }
 }
\ No newline at end of file
@@ -3,7 +3,8 @@ use crate::renderer::shapes::Shape;
 use glam::Vec2;
 
 pub struct ImageRect {
-    mesh: Mesh
+    mesh: Mesh,
+    size: Vec2,
 }
 
 impl ImageRect {
@@ -21,9 +22,14 @@ impl ImageRect {
             mesh: Mesh {
                 vertices,
                 indices
-            }
+            },
+            size,
         }
     }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
 }
 
 impl Shape for ImageRect {
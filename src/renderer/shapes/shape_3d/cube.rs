@@ -0,0 +1,96 @@
+use crate::renderer::shapes::mesh::{Mesh3D, Vertex3D};
+use crate::renderer::shapes::Shape3D;
+use glam::Vec3;
+
+/// 边长为 1 的立方体，每个面单独的顶点以保证法线正确
+pub struct Cube {
+    mesh: Mesh3D,
+}
+
+impl Cube {
+    pub fn new() -> Self {
+        // 每个面：法线、面内 u 轴、面内 v 轴，满足 u x v = 法线
+        let faces = [
+            (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+        ];
+
+        let corners = [
+            (-0.5, -0.5, [0.0, 1.0]),
+            ( 0.5, -0.5, [1.0, 1.0]),
+            ( 0.5,  0.5, [1.0, 0.0]),
+            (-0.5,  0.5, [0.0, 0.0]),
+        ];
+
+        let mut vertices = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+
+        for (normal, u, v) in faces {
+            let first = vertices.len() as u32;
+
+            for (cu, cv, uv) in corners {
+                let position = normal * 0.5 + u * cu + v * cv;
+                vertices.push(Vertex3D {
+                    position: position.to_array(),
+                    normal: normal.to_array(),
+                    uv,
+                    color: [1.0, 1.0, 1.0, 1.0],
+                });
+            }
+
+            indices.extend([first, first + 1, first + 2, first + 2, first + 3, first]);
+        }
+
+        Self {
+            mesh: Mesh3D {
+                vertices,
+                indices
+            }
+        }
+    }
+}
+
+impl Shape3D for Cube {
+    fn mesh(&self) -> &Mesh3D {
+        &self.mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triangles_are_counter_clockwise_facing_their_normal() {
+        let cube = Cube::new();
+        let mesh = cube.mesh();
+        assert_eq!(mesh.vertices.len(), 24);
+        assert_eq!(mesh.indices.len(), 36);
+
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+            let [pa, pb, pc] = [a, b, c].map(|v| Vec3::from_array(v.position));
+
+            // 同一个三角形的三个顶点属于同一个面，法线相同，且与逆时针绕序的面法线方向一致
+            let normal = Vec3::from_array(a.normal);
+            assert!([b, c].iter().all(|v| v.normal == a.normal));
+            let face = (pb - pa).cross(pc - pa);
+            assert!(face.dot(normal) > 0.0, "三角形 {triangle:?} 的绕序与法线 {normal} 相反");
+            assert!(face.normalize().abs_diff_eq(normal, 1e-6));
+        }
+    }
+
+    #[test]
+    fn normals_are_unit_and_on_the_face() {
+        for vertex in &Cube::new().mesh().vertices {
+            let normal = Vec3::from_array(vertex.normal);
+            assert!((normal.length() - 1.0).abs() < 1e-6);
+            // 顶点位于法线方向距中心 0.5 的面上
+            assert!((Vec3::from_array(vertex.position).dot(normal) - 0.5).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod cube;
+pub mod sphere;
\ No newline at end of file
@@ -0,0 +1,103 @@
+use crate::renderer::shapes::mesh::{Mesh3D, Vertex3D};
+use crate::renderer::shapes::Shape3D;
+use std::f32::consts::PI;
+
+/// 直径为 1 的经纬球
+pub struct Sphere {
+    mesh: Mesh3D,
+}
+
+impl Sphere {
+    /// `sectors` 为经线分段数，`stacks` 为纬线分段数
+    pub fn new(sectors: u32, stacks: u32) -> Self {
+        let sectors = sectors.max(3);
+        let stacks = stacks.max(2);
+
+        let mut vertices = Vec::with_capacity(((sectors + 1) * (stacks + 1)) as usize);
+        let mut indices = Vec::with_capacity((sectors * stacks * 6) as usize);
+
+        for i in 0..=stacks {
+            // 从北极到南极
+            let phi = PI * i as f32 / stacks as f32;
+
+            for j in 0..=sectors {
+                let theta = 2.0 * PI * j as f32 / sectors as f32;
+                let normal = [phi.sin() * theta.cos(), phi.cos(), phi.sin() * theta.sin()];
+
+                vertices.push(Vertex3D {
+                    position: normal.map(|n| n * 0.5),
+                    normal,
+                    uv: [j as f32 / sectors as f32, i as f32 / stacks as f32],
+                    color: [1.0, 1.0, 1.0, 1.0],
+                });
+            }
+        }
+
+        for i in 0..stacks {
+            for j in 0..sectors {
+                let k1 = i * (sectors + 1) + j;
+                let k2 = k1 + sectors + 1;
+
+                // 两极处的三角形退化，跳过
+                if i != 0 {
+                    indices.extend([k1, k1 + 1, k2]);
+                }
+                if i != stacks - 1 {
+                    indices.extend([k1 + 1, k2 + 1, k2]);
+                }
+            }
+        }
+
+        Self {
+            mesh: Mesh3D {
+                vertices,
+                indices
+            }
+        }
+    }
+}
+
+impl Shape3D for Sphere {
+    fn mesh(&self) -> &Mesh3D {
+        &self.mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    #[test]
+    fn triangles_are_counter_clockwise_facing_outward() {
+        for (sectors, stacks) in [(3, 2), (16, 8), (32, 17)] {
+            let sphere = Sphere::new(sectors, stacks);
+            let mesh = sphere.mesh();
+            // 两极各少一行三角形
+            assert_eq!(mesh.indices.len() as u32, (sectors * (stacks - 1) * 2) * 3);
+
+            for triangle in mesh.indices.chunks(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+                let [pa, pb, pc] = [a, b, c].map(|v| Vec3::from_array(v.position));
+
+                let face = (pb - pa).cross(pc - pa);
+                assert!(face.length() > 0.0, "三角形 {triangle:?} 退化");
+
+                // 每个顶点的法线都朝向三角形正面
+                for vertex in [a, b, c] {
+                    let normal = Vec3::from_array(vertex.normal);
+                    assert!(face.dot(normal) > 0.0, "三角形 {triangle:?} 的绕序与法线 {normal} 相反");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn normals_are_unit_and_radial() {
+        for vertex in &Sphere::new(16, 8).mesh().vertices {
+            let normal = Vec3::from_array(vertex.normal);
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+            assert!(Vec3::from_array(vertex.position).abs_diff_eq(normal * 0.5, 1e-6));
+        }
+    }
+}
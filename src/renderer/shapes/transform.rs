@@ -25,4 +25,9 @@ impl Transform {
             Vec3::new(self.position.x, self.position.y, 0.0),
         )
     }
+
+    /// 3D 变换矩阵，使用完整的位置和缩放
+    pub fn to_mat4_3d(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.position)
+    }
 }
\ No newline at end of file
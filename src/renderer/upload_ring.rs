@@ -0,0 +1,87 @@
+use log::info;
+use std::sync::Arc;
+use vulkano::buffer::allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo};
+use vulkano::buffer::{BufferContents, BufferUsage, Subbuffer};
+use vulkano::memory::allocator::{MemoryTypeFilter, StandardMemoryAllocator};
+use vulkano::DeviceSize;
+
+/// 每个竞技场的初始大小
+const INITIAL_ARENA_SIZE: DeviceSize = 64 * 1024;
+
+/// 每帧动态数据（实例、ImGui 顶点等）的上传环形分配器
+///
+/// 每个在途帧（`VulkanConfig::frames_in_flight`）占用一个 `SubbufferAllocator`，循环复用；
+/// 单次上传超过竞技场大小时按 2 的幂扩容，之后不再收缩
+pub struct UploadRing {
+    arenas: Vec<SubbufferAllocator>,
+    current: usize,
+}
+
+impl UploadRing {
+    pub fn new(allocator: Arc<StandardMemoryAllocator>, usage: BufferUsage, frames: usize) -> Self {
+        let arenas = (0..frames.max(1))
+            .map(|_| SubbufferAllocator::new(
+                allocator.clone(),
+                SubbufferAllocatorCreateInfo {
+                    arena_size: INITIAL_ARENA_SIZE,
+                    buffer_usage: usage,
+                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                    ..SubbufferAllocatorCreateInfo::default()
+                }
+            ))
+            .collect();
+
+        Self {
+            arenas,
+            current: 0,
+        }
+    }
+
+    /// 切换到下一帧使用的竞技场
+    pub fn next_frame(&mut self) {
+        self.current = (self.current + 1) % self.arenas.len();
+    }
+
+    /// 在当前帧竞技场中分配 `len` 个元素，空间不足时扩容
+    pub fn allocate<T: BufferContents>(&mut self, len: usize) -> Subbuffer<[T]> {
+        let arena = &self.arenas[self.current];
+
+        let required = (len * size_of::<T>()) as DeviceSize;
+        if required > arena.arena_size() {
+            let new_size = required.next_power_of_two();
+            info!("上传竞技场扩容: {} -> {} 字节", arena.arena_size(), new_size);
+            arena.set_arena_size(new_size);
+        }
+
+        arena
+            .allocate_slice::<T>(len as DeviceSize)
+            .unwrap_or_else(|e| panic!("上传缓冲区分配失败: {}", e))
+    }
+
+    /// 将迭代器中的数据直接写入映射内存，不产生中间拷贝
+    pub fn upload_iter<T, I>(&mut self, iter: I) -> Subbuffer<[T]>
+    where
+        T: BufferContents,
+        I: ExactSizeIterator<Item = T>,
+    {
+        self.upload_with_len(iter.len(), iter)
+    }
+
+    /// 同 `upload_iter`，用于长度已知但迭代器本身不提供长度的情况（如 `flat_map`）
+    pub fn upload_with_len<T, I>(&mut self, len: usize, iter: I) -> Subbuffer<[T]>
+    where
+        T: BufferContents,
+        I: Iterator<Item = T>,
+    {
+        let buffer = self.allocate::<T>(len);
+
+        {
+            let mut mapped = buffer.write().expect("写入上传缓冲区失败");
+            for (dst, src) in mapped.iter_mut().zip(iter) {
+                *dst = src;
+            }
+        }
+
+        buffer
+    }
+}
@@ -1,12 +1,20 @@
 use crate::renderer::shapes::mesh::AzerVertex;
 use std::sync::Arc;
-use vulkano::buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer};
+use vulkano::buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer};
 use vulkano::memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator};
 
 pub fn get_vbo_2d(
     vertices: Vec<AzerVertex>,
     memory_allocator: Arc<StandardMemoryAllocator>,
 ) -> Subbuffer<[AzerVertex]> {
+    get_vbo(vertices, memory_allocator)
+}
+
+/// 任意顶点类型的顶点缓冲
+pub fn get_vbo<V: BufferContents>(
+    vertices: Vec<V>,
+    memory_allocator: Arc<StandardMemoryAllocator>,
+) -> Subbuffer<[V]> {
     let vertex_buffer = Buffer::from_iter(
         memory_allocator,
         BufferCreateInfo {
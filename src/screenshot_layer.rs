@@ -0,0 +1,67 @@
+use azer::core::delta_time::DeltaTime;
+use azer::core::event::Event;
+use azer::core::input::InputState;
+use azer::core::layer::Layer;
+use azer::renderer::image_buffer_man::ImageBufferManager;
+use azer::renderer::renderer::Renderer;
+use chrono::Local;
+use imgui::Ui;
+use log::info;
+use winit::event::{ElementState, WindowEvent};
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+/// 按 F12 将当前帧保存到 screenshots 目录
+pub struct ScreenshotLayer {
+    pub key: KeyCode,
+    pub requested: bool,
+}
+
+impl ScreenshotLayer {
+    pub fn new() -> Self {
+        Self {
+            key: KeyCode::F12,
+            requested: false,
+        }
+    }
+}
+
+impl Layer for ScreenshotLayer {
+    fn on_ready(&mut self, _renderer: &mut Renderer) {
+        info!("ScreenshotLayer ready, 按 {:?} 截图", self.key);
+    }
+
+    fn on_update(&mut self, _delta: &DeltaTime, _input: &mut InputState) {
+
+    }
+
+    fn on_render(&mut self, renderer: &mut Renderer, _map: &mut ImageBufferManager) {
+        if self.requested {
+            self.requested = false;
+            let time_str = Local::now().format("%Y%m%d_%H%M%S_%3f");
+            renderer.capture_screenshot(format!("screenshots/azer_{}.png", time_str));
+        }
+    }
+
+    fn on_imgui_render(&mut self, _ui: &mut Ui) {
+
+    }
+
+    fn on_physics_update(&mut self, _delta: &DeltaTime) {
+
+    }
+
+    fn on_event(&mut self, event: &Event) {
+        if let WindowEvent::KeyboardInput { event: key_event, .. } = &event.event {
+            if key_event.state == ElementState::Pressed
+                && !key_event.repeat
+                && key_event.physical_key == PhysicalKey::Code(self.key)
+            {
+                self.requested = true;
+            }
+        }
+    }
+
+    fn on_close(&mut self) {
+        info!("ScreenshotLayer close");
+    }
+}
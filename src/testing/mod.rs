@@ -0,0 +1 @@
+pub mod snapshot;
\ No newline at end of file
@@ -0,0 +1,277 @@
+use crate::api::vulkan::{Vulkan, VulkanConfig};
+use crate::api::vulkan_helper;
+use crate::core::delta_time::DeltaTime;
+use crate::core::event::ResizeEvent;
+use crate::core::input::InputState;
+use crate::core::layer_stack::LayerStack;
+use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::renderer::Renderer;
+use image::{Rgba, RgbaImage};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+
+/// 设置该环境变量后，`assert_snapshot` 会用实际输出覆盖基准图像
+pub const UPDATE_SNAPSHOTS_ENV: &str = "AZER_UPDATE_SNAPSHOTS";
+
+/// 设置该环境变量后，没有 Vulkan 设备时 `SnapshotHarness::try_new` 直接失败而不是跳过，用于有 GPU 的 CI
+pub const REQUIRE_GPU_ENV: &str = "AZER_REQUIRE_GPU";
+
+const SNAPSHOT_DELTA: DeltaTime = DeltaTime::new_const(1.0 / 60.0);
+
+/// 无窗口快照测试环境：驱动图层栈渲染若干帧并读回帧缓冲
+pub struct SnapshotHarness {
+    pub vulkan: Vulkan,
+    pub renderer: Renderer,
+    pub map: ImageBufferManager,
+    pub clear_color: [f32; 4],
+}
+
+impl SnapshotHarness {
+    pub fn new(extent: [u32; 2]) -> Self {
+        let vulkan = Vulkan::new_headless(extent, VulkanConfig::default());
+        let mut map = ImageBufferManager::default();
+
+        let renderer = Renderer::new(
+            vulkan.device.clone(),
+            vulkan.queue.clone(),
+            vulkan.extent(),
+            vulkan.render_pass.clone(),
+            vulkan.frames_in_flight(),
+            &mut map
+        );
+
+        Self {
+            vulkan,
+            renderer,
+            map,
+            clear_color: [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// 没有可用的 Vulkan 设备时返回 None，测试可据此跳过而不是失败
+    ///
+    /// 设置了 `AZER_REQUIRE_GPU` 时没有设备会直接 panic，避免测试在 CI 中静默通过
+    pub fn try_new(extent: [u32; 2]) -> Option<Self> {
+        if !vulkan_helper::has_graphics_device() {
+            if std::env::var_os(REQUIRE_GPU_ENV).is_some() {
+                panic!("没有可用的 Vulkan 设备，但设置了 {REQUIRE_GPU_ENV}");
+            }
+
+            // 测试中通常没有初始化日志，直接输出到标准错误，`cargo test -- --nocapture` 时可见
+            eprintln!("没有可用的 Vulkan 设备，跳过无窗口渲染");
+            return None;
+        }
+
+        Some(Self::new(extent))
+    }
+
+    /// 依次调用 on_ready 和 on_resize，然后以固定步长执行 `frames` 帧 on_update + on_render，返回最后一帧
+    ///
+    /// 图片在首次绘制的那一帧仍是占位纹理，需要至少渲染两帧
+    pub fn run(&mut self, layer_stack: &mut LayerStack, frames: usize) -> RgbaImage {
+        let mut input = InputState::default();
+
+        layer_stack.iter_mut().for_each(|layer| layer.on_ready(&mut self.renderer));
+
+        let [width, height] = self.vulkan.extent();
+        let resize = ResizeEvent {
+            width,
+            height,
+            scaling: self.vulkan.scaling([width, height], 1.0),
+        };
+        layer_stack.iter_mut().for_each(|layer| layer.on_resize(&resize));
+
+        for _ in 0..frames.max(1) {
+            layer_stack.iter_mut().for_each(|layer| layer.on_update(&SNAPSHOT_DELTA, &mut input));
+
+            self.vulkan.render_offscreen(&mut self.renderer, layer_stack, self.clear_color, &mut self.map);
+
+            // 保证下一帧使用真实纹理而不是占位纹理，输出与解码耗时无关
+            self.renderer.wait_for_assets();
+        }
+
+        self.vulkan.read_pixels()
+    }
+}
+
+/// 两张图像逐像素比较的结果
+pub struct ImageDiff {
+    /// 任一通道差值超过容差的像素数
+    pub mismatched_pixels: usize,
+    /// 所有像素中最大的通道差值
+    pub max_delta: u8,
+    /// 差异图：不一致的像素标红，其余像素为灰度化的基准图
+    pub diff: RgbaImage,
+}
+
+impl ImageDiff {
+    pub fn is_match(&self) -> bool {
+        self.mismatched_pixels == 0
+    }
+}
+
+/// 逐像素比较两张尺寸相同的图像，通道差值不超过 `tolerance` 视为一致
+pub fn diff_images(actual: &RgbaImage, expected: &RgbaImage, tolerance: u8) -> ImageDiff {
+    assert_eq!(actual.dimensions(), expected.dimensions(), "比较的图像尺寸不一致");
+
+    let mut mismatched_pixels = 0;
+    let mut max_delta = 0;
+
+    let diff = RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let a = actual.get_pixel(x, y);
+        let e = expected.get_pixel(x, y);
+
+        let delta = a.0.iter()
+            .zip(e.0.iter())
+            .map(|(a, e)| a.abs_diff(*e))
+            .max()
+            .unwrap_or(0);
+
+        max_delta = max_delta.max(delta);
+
+        if delta > tolerance {
+            mismatched_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let luma = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 3 / 2) as u8;
+            Rgba([luma, luma, luma, 255])
+        }
+    });
+
+    ImageDiff {
+        mismatched_pixels,
+        max_delta,
+        diff,
+    }
+}
+
+/// 将实际输出与 `golden_path` 处的基准 PNG 比较，不一致时 panic
+///
+/// 失败时会在基准图旁写出 `*.actual.png` 和 `*.diff.png`；
+/// 基准图不存在或设置了 `AZER_UPDATE_SNAPSHOTS` 时写入新的基准图
+pub fn assert_snapshot(actual: &RgbaImage, golden_path: impl AsRef<Path>, tolerance: u8) {
+    let golden_path = golden_path.as_ref();
+
+    if std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+        save(actual, golden_path);
+        info!("已更新快照基准图: {}", golden_path.display());
+        return;
+    }
+
+    if !golden_path.exists() {
+        save(actual, golden_path);
+        panic!("快照基准图不存在，已生成: {}，请检查后提交", golden_path.display());
+    }
+
+    let expected = image::open(golden_path)
+        .unwrap_or_else(|e| panic!("读取快照基准图失败 {}: {}", golden_path.display(), e))
+        .to_rgba8();
+
+    let actual_path = sibling_path(golden_path, "actual");
+    let diff_path = sibling_path(golden_path, "diff");
+
+    if actual.dimensions() != expected.dimensions() {
+        save(actual, &actual_path);
+        panic!(
+            "快照尺寸不一致 {}: 实际 {:?}，基准 {:?}",
+            golden_path.display(),
+            actual.dimensions(),
+            expected.dimensions()
+        );
+    }
+
+    let diff = diff_images(actual, &expected, tolerance);
+
+    if !diff.is_match() {
+        save(actual, &actual_path);
+        save(&diff.diff, &diff_path);
+        panic!(
+            "快照不一致 {}: {} 个像素超出容差 {}（最大差值 {}），差异图: {}",
+            golden_path.display(),
+            diff.mismatched_pixels,
+            tolerance,
+            diff.max_delta,
+            diff_path.display()
+        );
+    }
+
+    // 清理上一次失败留下的文件
+    for path in [actual_path, diff_path] {
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!("删除旧的快照输出失败 {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+fn sibling_path(golden_path: &Path, suffix: &str) -> PathBuf {
+    let stem = golden_path.file_stem().unwrap_or_default().to_string_lossy();
+    golden_path.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+fn save(image: &RgbaImage, path: &Path) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .unwrap_or_else(|e| panic!("创建快照目录失败 {}: {}", parent.display(), e));
+    }
+
+    image.save(path)
+        .unwrap_or_else(|e| panic!("写入快照图像失败 {}: {}", path.display(), e));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba(color))
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let image = solid(4, 3, [10, 20, 30, 255]);
+        let diff = diff_images(&image, &image, 0);
+
+        assert!(diff.is_match());
+        assert_eq!(diff.max_delta, 0);
+        assert_eq!(diff.diff.dimensions(), (4, 3));
+    }
+
+    #[test]
+    fn delta_equal_to_tolerance_matches() {
+        let expected = solid(2, 2, [100, 100, 100, 255]);
+        let actual = solid(2, 2, [103, 97, 100, 255]);
+
+        let diff = diff_images(&actual, &expected, 3);
+        assert!(diff.is_match());
+        assert_eq!(diff.max_delta, 3);
+
+        let diff = diff_images(&actual, &expected, 2);
+        assert!(!diff.is_match());
+        assert_eq!(diff.mismatched_pixels, 4);
+        assert_eq!(diff.max_delta, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "比较的图像尺寸不一致")]
+    fn size_mismatch_panics() {
+        diff_images(&solid(2, 2, [0; 4]), &solid(2, 3, [0; 4]), 0);
+    }
+
+    #[test]
+    fn diff_image_marks_mismatched_pixels() {
+        let expected = solid(2, 1, [90, 120, 150, 255]);
+        let mut actual = expected.clone();
+        actual.put_pixel(1, 0, Rgba([90, 120, 150, 0]));
+
+        let diff = diff_images(&actual, &expected, 0);
+        assert_eq!(diff.mismatched_pixels, 1);
+        assert_eq!(diff.max_delta, 255);
+
+        // 一致的像素为基准图灰度的一半，不一致的像素标红
+        assert_eq!(*diff.diff.get_pixel(0, 0), Rgba([60, 60, 60, 255]));
+        assert_eq!(*diff.diff.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+    }
+}
@@ -2,22 +2,25 @@ use crate::api::vulkan_helper;
 use crate::core::core::print_mem;
 use crate::renderer::frame_commands::FrameCommands;
 use crate::renderer::image_buffer_man::ImageBufferManager;
+use crate::renderer::render_target::RenderTargetHandle;
 use crate::renderer::shaders::Shader;
-use imgui::{DrawCmd, DrawIdx};
+use crate::renderer::upload_ring::UploadRing;
+use imgui::{DrawCmd, DrawIdx, TextureId};
 use log::error;
 use smallvec::smallvec;
-use std::fmt::Debug;
+use std::collections::HashMap;
 use std::sync::Arc;
 use vulkano::{
-    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    buffer::{BufferContents, BufferUsage, Subbuffer},
     descriptor_set::allocator::StandardDescriptorSetAllocator,
     descriptor_set::{DescriptorSet, WriteDescriptorSet},
     device::Device,
     format::Format,
     image::sampler::{Sampler, SamplerCreateInfo},
     image::view::ImageView,
-    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    memory::allocator::StandardMemoryAllocator,
     pipeline::graphics::color_blend::{AttachmentBlend, BlendFactor, BlendOp, ColorBlendAttachmentState, ColorBlendState},
+    pipeline::graphics::depth_stencil::DepthStencilState,
     pipeline::graphics::input_assembly::InputAssemblyState,
     pipeline::graphics::multisample::MultisampleState,
     pipeline::graphics::rasterization::RasterizationState,
@@ -98,11 +101,10 @@ impl Shader for ImGuiShader {
     fn vs(&self) -> &Arc<ShaderModule> {
         &self.vs
     }
+}
 
-    fn load(device: Arc<Device>) -> Result<Self, Validated<VulkanError>>
-    where
-        Self: Sized + Clone + Debug
-    {
+impl ImGuiShader {
+    pub fn load(device: Arc<Device>) -> Result<Self, Validated<VulkanError>> {
         Ok(Self {
             vs: vs::load(device.clone())?,
             fs: fs::load(device.clone())?,
@@ -112,8 +114,13 @@ impl Shader for ImGuiShader {
 
 pub struct ImGuiRenderer {
     pipeline: Arc<GraphicsPipeline>,
-    memory_allocator: Arc<StandardMemoryAllocator>,
+    upload_ring: UploadRing,
+    /// 字体纹理
     set: Arc<DescriptorSet>,
+    set_allocator: Arc<StandardDescriptorSetAllocator>,
+    sampler: Arc<Sampler>,
+    /// 渲染目标纹理的描述符集，图像变化时重建
+    texture_sets: HashMap<TextureId, (Arc<ImageView>, Arc<DescriptorSet>)>,
 }
 
 #[repr(C)]
@@ -141,6 +148,7 @@ impl ImGuiRenderer {
         render_pass: Arc<RenderPass>,
         memory_allocator: Arc<StandardMemoryAllocator>,
         set_allocator: Arc<StandardDescriptorSetAllocator>,
+        frames_in_flight: usize,
         imgui: &mut imgui::Context,
         map: &mut ImageBufferManager
     ) -> Self {
@@ -181,69 +189,75 @@ impl ImGuiRenderer {
             []
         ).unwrap();
 
+        let upload_ring = UploadRing::new(
+            memory_allocator,
+            BufferUsage::VERTEX_BUFFER | BufferUsage::INDEX_BUFFER,
+            frames_in_flight
+        );
+
         Self {
             pipeline: pipeline.clone(),
-            memory_allocator,
-            set
+            upload_ring,
+            set,
+            set_allocator,
+            sampler,
+            texture_sets: HashMap::new(),
         }
     }
 
+    /// 纹理 ID 对应的描述符集：渲染目标见 `RenderTargetHandle::texture_id`，其余使用字体纹理
+    fn texture_set(&mut self, id: TextureId, textures: &[Arc<ImageView>]) -> Arc<DescriptorSet> {
+        let Some(view) = RenderTargetHandle::from_texture_id(id).and_then(|handle| textures.get(handle.0 as usize)) else {
+            return self.set.clone();
+        };
+
+        if let Some((cached, set)) = self.texture_sets.get(&id) {
+            if Arc::ptr_eq(cached, view) {
+                return set.clone();
+            }
+        }
+
+        let set = DescriptorSet::new(
+            self.set_allocator.clone(),
+            self.pipeline.layout().set_layouts()[0].clone(),
+            [WriteDescriptorSet::image_view_sampler(0, view.clone(), self.sampler.clone())],
+            []
+        ).unwrap_or_else(|e| panic!("创建 ImGui 纹理描述符集失败: {}", e));
+
+        self.texture_sets.insert(id, (view.clone(), set.clone()));
+        set
+    }
+
     pub fn get_vbo_and_ibi_from_draw_data(
         &mut self,
         draw_data: &imgui::DrawData,
     )-> (Subbuffer<[VertexForImGui]>, Subbuffer<[DrawIdx]>) {
-        let mut vertices = Vec::new();
-        let mut indices = Vec::new();
-
-        for draw_list in draw_data.draw_lists() {
-            for v in draw_list.vtx_buffer() {
-                vertices.push(VertexForImGui {
-                    pos: [v.pos[0], v.pos[1]],
-                    uv: [v.uv[0], v.uv[1]],
-                    color: v.col
-                })
-            }
+        // 直接写入上传环形缓冲，不再为每帧创建新缓冲
+        let vertices = draw_data.draw_lists()
+            .flat_map(|draw_list| draw_list.vtx_buffer().iter())
+            .map(|v| VertexForImGui {
+                pos: [v.pos[0], v.pos[1]],
+                uv: [v.uv[0], v.uv[1]],
+                color: v.col
+            });
 
-            for i in draw_list.idx_buffer() {
-                indices.push(*i)
-            }
-        }
+        let indices = draw_data.draw_lists()
+            .flat_map(|draw_list| draw_list.idx_buffer().iter().copied());
 
-        let vertex_buffers = Buffer::from_iter(
-            self.memory_allocator.clone(),
-            BufferCreateInfo {
-                usage: BufferUsage::VERTEX_BUFFER,
-                ..BufferCreateInfo::default()
-            },
-            AllocationCreateInfo {
-                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-                ..AllocationCreateInfo::default()
-            },
-            vertices
-        ).expect("Failed to create vertex buffer");
-
-        let index_buffers = Buffer::from_iter(
-            self.memory_allocator.clone(),
-            BufferCreateInfo {
-                usage: BufferUsage::INDEX_BUFFER,
-                ..BufferCreateInfo::default()
-            },
-            AllocationCreateInfo {
-                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-                ..AllocationCreateInfo::default()
-            },
-            indices,
-        ).unwrap();
+        let vertex_buffers = self.upload_ring.upload_with_len(draw_data.total_vtx_count as usize, vertices);
+        let index_buffers = self.upload_ring.upload_with_len(draw_data.total_idx_count as usize, indices);
 
         (vertex_buffers, index_buffers)
     }
 
-    pub fn draw(&mut self, frame: &mut FrameCommands, draw_data: &imgui::DrawData, viewport: Viewport) {
+    /// `textures` 为各渲染目标的图像，`ui.image` 可以通过 `RenderTargetHandle::texture_id` 显示它们
+    pub fn draw(&mut self, frame: &mut FrameCommands, draw_data: &imgui::DrawData, viewport: Viewport, textures: &[Arc<ImageView>]) {
 
         if draw_data.total_vtx_count == 0 || draw_data.total_idx_count == 0 {
             return; // 当前帧 ImGui 没有可绘制数据
         }
 
+        self.upload_ring.next_frame();
         let (vbo, ibo) = self.get_vbo_and_ibi_from_draw_data(draw_data);
 
         let display_size = draw_data.display_size;
@@ -261,6 +275,7 @@ impl ImGuiRenderer {
 
         let mut vertex_offset: i32 = 0;
         let mut index_offset: u32  = 0;
+        let mut bound_texture = None;
 
         frame.builder
             .bind_pipeline_graphics(self.pipeline.clone())
@@ -292,6 +307,19 @@ impl ImGuiRenderer {
                 for cmd in draw_list.commands() {
                     match cmd {
                         DrawCmd::Elements { count, cmd_params } => {
+                            if bound_texture != Some(cmd_params.texture_id) {
+                                let set = self.texture_set(cmd_params.texture_id, textures);
+                                frame.builder
+                                    .bind_descriptor_sets(
+                                        PipelineBindPoint::Graphics,
+                                        self.pipeline.layout().clone(),
+                                        0,
+                                        set,
+                                    )
+                                    .unwrap();
+                                bound_texture = Some(cmd_params.texture_id);
+                            }
+
                             let clip = cmd_params.clip_rect;
 
                             let clip_x1 = clip[0] - display_pos[0];
@@ -403,8 +431,9 @@ impl ImGuiRenderer {
                         ..ColorBlendAttachmentState::default()
                     }
                 )),
+                // ImGui 始终绘制在最上层，不做深度测试
+                depth_stencil_state: subpass.has_depth().then(DepthStencilState::default),
                 subpass: Some(subpass.into()),
-                depth_stencil_state: None,
                 dynamic_state: [DynamicState::Scissor, DynamicState::Viewport].into_iter().collect(),
                 ..GraphicsPipelineCreateInfo::layout(layout)
             }
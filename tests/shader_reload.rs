@@ -0,0 +1,89 @@
+//! 自定义着色器的热重载测试，没有 Vulkan 设备时跳过
+
+use azer::core::layer_stack::LayerStack;
+use azer::testing::snapshot::SnapshotHarness;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const VERTEX_SHADER: &str = r"
+#version 460
+
+layout(location = 0) in vec4 position;
+layout(location = 1) in vec2 uv;
+layout(location = 2) in vec4 color;
+layout(location = 3) in mat4 i_transform;
+layout(location = 7) in vec4 i_color;
+layout(location = 8) in vec4 i_uv_rect;
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_proj;
+} pc;
+
+layout(location = 0) out vec2 v_uv;
+layout(location = 1) out vec4 v_color;
+
+void main() {
+    v_uv = i_uv_rect.xy + uv * i_uv_rect.zw;
+    v_color = color * i_color;
+    gl_Position = pc.view_proj * i_transform * position;
+}
+";
+
+const FRAGMENT_SHADER: &str = r"
+#version 460
+
+layout(location = 0) in vec2 v_uv;
+layout(location = 1) in vec4 v_color;
+
+layout(set = 0, binding = 0) uniform sampler2D tex;
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    f_color = texture(tex, v_uv) * v_color;
+}
+";
+
+const BROKEN_FRAGMENT_SHADER: &str = r"
+#version 460
+
+void main() {
+    this is not glsl
+}
+";
+
+fn write(path: &Path, contents: &str) {
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn shader_that_failed_first_load_recovers_after_fix() {
+    let Some(mut harness) = SnapshotHarness::try_new([16, 16]) else {
+        return;
+    };
+
+    let dir = std::env::temp_dir().join(format!("azer-shader-reload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let vs_path = dir.join("sprite.vert");
+    let fs_path = dir.join("sprite.frag");
+    write(&vs_path, VERTEX_SHADER);
+    write(&fs_path, BROKEN_FRAGMENT_SHADER);
+
+    let (vs, fs) = (vs_path.to_string_lossy().to_string(), fs_path.to_string_lossy().to_string());
+    assert!(harness.renderer.load_shader(&vs, &fs).is_err());
+    assert!(harness.renderer.shader_error().is_some());
+
+    write(&fs_path, FRAGMENT_SHADER);
+
+    // 文件事件是异步送达的，逐帧渲染直到着色器被重新加载
+    let mut layer_stack = LayerStack::new();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while harness.renderer.shader_error().is_some() && Instant::now() < deadline {
+        harness.run(&mut layer_stack, 1);
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(harness.renderer.shader_error(), None);
+}
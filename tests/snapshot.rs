@@ -0,0 +1,153 @@
+//! 2D 绘制的快照回归测试
+//!
+//! 没有 Vulkan 设备时跳过；修改渲染后确认输出正确，可设置 `AZER_UPDATE_SNAPSHOTS=1` 重新生成基准图
+
+use azer::core::delta_time::DeltaTime;
+use azer::core::event::Event;
+use azer::core::input::InputState;
+use azer::core::layer::Layer;
+use azer::core::layer_stack::LayerStack;
+use azer::renderer::camera::camera2d::Camera2D;
+use azer::renderer::camera::Camera;
+use azer::renderer::image_buffer_man::ImageBufferManager;
+use azer::renderer::renderer::Renderer;
+use azer::renderer::shapes::transform::Transform;
+use azer::testing::snapshot::{assert_snapshot, SnapshotHarness};
+use glam::Vec2;
+use image::{Rgba, RgbaImage};
+use imgui::Ui;
+use std::path::PathBuf;
+
+const EXTENT: [u32; 2] = [64, 64];
+
+/// 颜色转换为 UNORM 时的舍入误差
+const TOLERANCE: u8 = 2;
+
+/// 用固定相机执行一段绘制的图层，1 个世界单位对应 1 个像素，原点在画面中心
+struct DrawLayer {
+    camera: Camera2D,
+    draw: Box<dyn Fn(&mut Renderer) + Send + Sync>,
+}
+
+impl DrawLayer {
+    fn new(draw: impl Fn(&mut Renderer) + Send + Sync + 'static) -> Self {
+        let half_height = EXTENT[1] as f32 / 2.0;
+        let aspect_ratio = EXTENT[0] as f32 / EXTENT[1] as f32;
+
+        let mut camera = Camera2D::new(aspect_ratio, half_height, -1.0, 1.0, Vec2::ZERO);
+        camera.update();
+
+        Self {
+            camera,
+            draw: Box::new(draw),
+        }
+    }
+}
+
+impl Layer for DrawLayer {
+    fn on_ready(&mut self, _renderer: &mut Renderer) {}
+
+    fn on_update(&mut self, _delta: &DeltaTime, _input: &mut InputState) {}
+
+    fn on_render(&mut self, renderer: &mut Renderer, _map: &mut ImageBufferManager) {
+        renderer.update_camera(*self.camera.get_view_projection_matrix());
+        (self.draw)(renderer);
+    }
+
+    fn on_imgui_render(&mut self, _ui: &mut Ui) {}
+
+    fn on_physics_update(&mut self, _delta: &DeltaTime) {}
+
+    fn on_event(&mut self, _event: &Event) {}
+
+    fn on_close(&mut self) {}
+}
+
+fn golden(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots").join(format!("{name}.png"))
+}
+
+fn render(layer: DrawLayer, frames: usize) -> Option<RgbaImage> {
+    let mut harness = SnapshotHarness::try_new(EXTENT)?;
+
+    let mut layer_stack = LayerStack::new();
+    layer_stack.push(Box::new(layer));
+
+    Some(harness.run(&mut layer_stack, frames))
+}
+
+fn transform(position: Vec2, scale: Vec2) -> Transform {
+    Transform {
+        position: position.extend(0.0),
+        scale: scale.extend(1.0),
+        ..Transform::default()
+    }
+}
+
+#[test]
+fn draw_rectangle_matches_snapshot() {
+    let layer = DrawLayer::new(|renderer| {
+        // 先提交但 z_index 更大，应盖在红色矩形上
+        renderer.set_z_index(1);
+        renderer.draw_rectangle(transform(Vec2::ZERO, Vec2::splat(4.0)), [0.0, 1.0, 0.0, 1.0]);
+        renderer.set_z_index(0);
+
+        renderer.draw_rectangle(transform(Vec2::new(-8.0, 4.0), Vec2::new(16.0, 8.0)), [1.0, 0.0, 0.0, 1.0]);
+        renderer.draw_rectangle(transform(Vec2::new(12.0, -10.0), Vec2::new(8.0, 12.0)), [0.2, 0.4, 1.0, 1.0]);
+    });
+
+    let Some(actual) = render(layer, 1) else {
+        return;
+    };
+
+    assert_snapshot(&actual, golden("draw_rectangle"), TOLERANCE);
+}
+
+#[test]
+fn draw_image_matches_snapshot() {
+    // 8x4 的图片，四个象限颜色不同，用于检查方向和 UV
+    let image = RgbaImage::from_fn(8, 4, |x, y| match (x < 4, y < 2) {
+        (true, true) => Rgba([255, 0, 0, 255]),
+        (false, true) => Rgba([0, 255, 0, 255]),
+        (true, false) => Rgba([0, 0, 255, 255]),
+        (false, false) => Rgba([255, 255, 255, 255]),
+    });
+
+    let path = std::env::temp_dir().join(format!("azer-snapshot-quadrants-{}.png", std::process::id()));
+    image.save(&path).unwrap();
+    let image_path = path.to_string_lossy().to_string();
+
+    let layer = DrawLayer::new(move |renderer| {
+        renderer.draw_image(transform(Vec2::new(8.0, -8.0), Vec2::ONE), &image_path);
+        renderer.draw_image(transform(Vec2::new(-20.0, 20.0), Vec2::ONE), &image_path);
+    });
+
+    // 第一帧绘制占位纹理，第二帧才是解码后的图片
+    let actual = render(layer, 2);
+    let _ = std::fs::remove_file(&path);
+
+    let Some(actual) = actual else {
+        return;
+    };
+
+    assert_snapshot(&actual, golden("draw_image"), TOLERANCE);
+}
+
+#[test]
+fn transform_is_applied_once() {
+    // 平移后的矩形只在目标位置出现一次，变换重复应用时会偏移并放大
+    let layer = DrawLayer::new(|renderer| {
+        renderer.draw_rectangle(transform(Vec2::new(10.0, 10.0), Vec2::splat(2.0)), [1.0, 1.0, 1.0, 1.0]);
+    });
+
+    let Some(actual) = render(layer, 1) else {
+        return;
+    };
+
+    let lit: Vec<(u32, u32)> = actual.enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel[0] > 127)
+        .map(|(x, y, _)| (x, y))
+        .collect();
+
+    assert_eq!(lit, [(41, 21), (42, 21), (41, 22), (42, 22)]);
+}